@@ -1,76 +1,368 @@
+use crate::error::{Error, Result};
+use crate::money::{amount, Currency, Decimal, Money};
 use crate::options::Options;
 use crate::requests::Requests;
-use chrono::{DateTime, Utc};
-use log::debug;
-use serde::{Deserialize, Serialize};
-use std::error::Error;
+use crate::response::ApiResponse;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
+use log::{debug, warn};
+use reqwest::Url;
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
 /// Link inside the results of Accounts API.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Link {
     pub href: String,
 }
 
+/// Defines enum for string values of the API.
+///
+/// Values not known by this version of the SDK, including known values in
+/// another case, are kept as is in the Unknown variant. New values added to
+/// the API do not break deserialization and are serialized back unchanged.
+macro_rules! api_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            /// Value not known by this version of the SDK.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Returns the value used by the API.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)+
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> $name {
+                match value {
+                    $($value => $name::$variant,)+
+                    _ => $name::Unknown(value.to_string()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<$name, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok($name::from(value.as_str()))
+            }
+        }
+    };
+}
+
+api_enum! {
+    /// Scheme of an account identifier.
+    IdentifierScheme {
+        /// International Bank Account Number.
+        Iban => "IBAN",
+    }
+}
+
+api_enum! {
+    /// Describes whether a transaction is a debit or credit transaction.
+    CreditDebitIndicator {
+        /// Money was taken from the account.
+        Debit => "debit",
+        /// Money was added to the account.
+        Credit => "credit",
+    }
+}
+
+api_enum! {
+    /// Status of a transaction.
+    TransactionStatus {
+        /// Transaction has been authorised.
+        Authorised => "Authorised",
+        /// Transaction is waiting for authorisation.
+        AwaitingAuthorisation => "AwaitingAuthorisation",
+        /// Authorisation of the transaction was rejected.
+        Rejected => "Rejected",
+        /// Transaction was revoked.
+        Revoked => "Revoked",
+    }
+}
+
 /// Describes a single Account
-#[derive(Deserialize, Debug)]
+///
+/// Serialized in the format of the API with the balance as a JSON number.
+/// Serializing fails if the balance is not in the currency of the account.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "AccountData")]
 pub struct Account {
     /// A surrogate identifier for the bank account.
-    #[serde(rename = "accountId")]
     pub account_id: String,
     /// Account name. This is a name assigned by the servicer and bears no significance to the
     /// user.
     pub name: String,
     /// Nickname of the account, if assigned by the owner of the account.
     pub nickname: Option<String>,
-    /// Current balance of the account. Note: This field will only be returned after the end user
-    /// has provided consent to the consuming application.
-    pub balance: Option<f64>,
-    /// Code of the currency of the account. All currency codes are ISO 4217-compliant strings.
-    pub currency: String,
-    /// "IBAN".
-    #[serde(rename = "identifierScheme")]
-    pub identifier_scheme: String,
+    /// Current balance of the account in the currency of the account. Note: This field will only
+    /// be returned after the end user has provided consent to the consuming application.
+    pub balance: Option<Money>,
+    /// Currency of the account.
+    pub currency: Currency,
+    /// Scheme of the account identifier, usually IBAN.
+    pub identifier_scheme: IdentifierScheme,
     /// Account identifier. Follows the scheme described by the field identifierScheme.
     pub identifier: String,
     /// Identifier of the scheme used for identifying the servicer.
-    #[serde(rename = "servicerScheme")]
     pub servicer_scheme: String,
     /// Identifier of the servicing bank. Follows the scheme described by the servicerScheme
     /// parameter.
-    #[serde(rename = "servicerIdentifier")]
     pub servicer_identifier: String,
 }
 
+/// Account as sent by the API, before the balance is combined with the
+/// currency.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountData {
+    account_id: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>,
+    #[serde(
+        default,
+        with = "amount::option_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    balance: Option<Decimal>,
+    currency: Currency,
+    identifier_scheme: IdentifierScheme,
+    identifier: String,
+    servicer_scheme: String,
+    servicer_identifier: String,
+}
+
+impl From<AccountData> for Account {
+    fn from(data: AccountData) -> Account {
+        let balance = data
+            .balance
+            .map(|balance| Money::new(balance, data.currency.clone()));
+        Account {
+            account_id: data.account_id,
+            name: data.name,
+            nickname: data.nickname,
+            balance,
+            currency: data.currency,
+            identifier_scheme: data.identifier_scheme,
+            identifier: data.identifier,
+            servicer_scheme: data.servicer_scheme,
+            servicer_identifier: data.servicer_identifier,
+        }
+    }
+}
+
+impl Serialize for Account {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if let Some(balance) = &self.balance {
+            check_currency(&self.currency, balance).map_err(ser::Error::custom)?;
+        }
+        AccountData {
+            account_id: self.account_id.clone(),
+            name: self.name.clone(),
+            nickname: self.nickname.clone(),
+            balance: self.balance.as_ref().map(|balance| balance.amount()),
+            currency: self.currency.clone(),
+            identifier_scheme: self.identifier_scheme.clone(),
+            identifier: self.identifier.clone(),
+            servicer_scheme: self.servicer_scheme.clone(),
+            servicer_identifier: self.servicer_identifier.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Checks that the amount is in the currency sent with it to the API.
+fn check_currency(currency: &Currency, amount: &Money) -> Result<()> {
+    if amount.currency() != currency {
+        return Err(Error::Config(format!(
+            "amount {} is not in currency {}",
+            amount, currency
+        )));
+    }
+    Ok(())
+}
+
 /// Describes a list of Accounts
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AccountList {
     pub accounts: Vec<Account>,
 }
 
+/// Format of the date-times in the query. The API does not accept a timezone.
+const QUERY_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
 /// Optional parameters to fetch transactions.
-#[derive(Serialize, Debug)]
+///
+/// Use TransactionParams::builder to construct the parameters:
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use op_api_sdk::apis::accounts::TransactionParams;
+///
+/// let params = TransactionParams::builder()
+///     .from(NaiveDate::from_ymd_opt(2020, 10, 1).unwrap())
+///     .to(NaiveDate::from_ymd_opt(2020, 10, 31).unwrap())
+///     .page_size(50)
+///     .build()
+///     .unwrap();
+/// assert_eq!(Some(50), params.page_size);
+/// ```
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
 pub struct TransactionParams {
-    /// ISO 8601-compatible date-time string representing the earliest date-time from which
-    /// transactions will be queried. Timezone must not be set. Set time to to 00:00:00 for
-    /// Date-only queries.
-    pub from_booking_datetime: Option<DateTime<Utc>>,
-    /// ISO 8601-compatible date-time string representing the latest date-time up to which
-    /// transactions will be queried. Timezone must not be set. Set time to 00:00:00 for Date-only
-    /// queries.
-    pub to_booking_datetime: Option<DateTime<Utc>>,
+    /// Earliest booking date-time from which transactions will be queried. Time is 00:00:00 for
+    /// date-only queries.
+    #[serde(
+        rename = "fromBookingDateTime",
+        serialize_with = "serialize_query_datetime",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_booking_datetime: Option<NaiveDateTime>,
+    /// Latest booking date-time up to which transactions will be queried. Time is 00:00:00 for
+    /// date-only queries.
+    #[serde(
+        rename = "toBookingDateTime",
+        serialize_with = "serialize_query_datetime",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_booking_datetime: Option<NaiveDateTime>,
     /// Number of transactions to be returned per each page.
+    #[serde(rename = "pageSize", skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u32>,
     /// Paging token used to retrieve the next page of data. Tokens are available in the links
     /// located in the _links object.
+    #[serde(rename = "forwardPagingToken", skip_serializing_if = "Option::is_none")]
     pub forward_paging_token: Option<String>,
 }
 
+impl TransactionParams {
+    /// Creates new TransactionParamsBuilder.
+    pub fn builder() -> TransactionParamsBuilder {
+        TransactionParamsBuilder::default()
+    }
+}
+
+/// Serializes date-time without timezone for the query.
+fn serialize_query_datetime<S: Serializer>(
+    datetime: &Option<NaiveDateTime>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match datetime {
+        Some(datetime) => {
+            serializer.serialize_str(&datetime.format(QUERY_DATETIME_FORMAT).to_string())
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Date or date-time accepted by TransactionParamsBuilder.
+///
+/// Dates are queried from 00:00:00. Date-times with a timezone are queried
+/// with their local time, the timezone itself is not sent.
+pub trait QueryDateTime {
+    /// Converts the value to date-time without timezone.
+    fn to_query_datetime(&self) -> NaiveDateTime;
+}
+
+impl QueryDateTime for NaiveDate {
+    fn to_query_datetime(&self) -> NaiveDateTime {
+        self.and_time(NaiveTime::MIN)
+    }
+}
+
+impl QueryDateTime for NaiveDateTime {
+    fn to_query_datetime(&self) -> NaiveDateTime {
+        *self
+    }
+}
+
+impl<Tz: TimeZone> QueryDateTime for DateTime<Tz> {
+    fn to_query_datetime(&self) -> NaiveDateTime {
+        self.naive_local()
+    }
+}
+
+/// Builder for TransactionParams.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionParamsBuilder {
+    params: TransactionParams,
+}
+
+impl TransactionParamsBuilder {
+    /// Sets earliest booking date or date-time of the transactions.
+    pub fn from<T: QueryDateTime>(mut self, from: T) -> TransactionParamsBuilder {
+        self.params.from_booking_datetime = Some(from.to_query_datetime());
+        self
+    }
+
+    /// Sets latest booking date or date-time of the transactions.
+    pub fn to<T: QueryDateTime>(mut self, to: T) -> TransactionParamsBuilder {
+        self.params.to_booking_datetime = Some(to.to_query_datetime());
+        self
+    }
+
+    /// Sets number of transactions returned per page.
+    pub fn page_size(mut self, page_size: u32) -> TransactionParamsBuilder {
+        self.params.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets paging token of the page to fetch.
+    pub fn forward_paging_token(mut self, token: &str) -> TransactionParamsBuilder {
+        self.params.forward_paging_token = Some(token.to_string());
+        self
+    }
+
+    /// Validates and builds TransactionParams.
+    pub fn build(self) -> Result<TransactionParams> {
+        let params = self.params;
+        if params.page_size == Some(0) {
+            return Err(Error::Config(String::from("page size must be positive")));
+        }
+        if let (Some(from), Some(to)) = (params.from_booking_datetime, params.to_booking_datetime) {
+            if from > to {
+                return Err(Error::Config(format!(
+                    "from date-time {} is after to date-time {}",
+                    from, to
+                )));
+            }
+        }
+        Ok(params)
+    }
+}
+
 /// Describes a single party in Transaction.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TransactionParty {
-    /// Account identifier schema.
+    /// Scheme of the account identifier.
     #[serde(rename = "accountIdentifierType")]
-    pub account_identifier_type: String,
+    pub account_identifier_type: IdentifierScheme,
     /// Name of the account.
     #[serde(rename = "accountName")]
     pub account_name: String,
@@ -86,33 +378,31 @@ pub struct TransactionParty {
 }
 
 /// Describes a single Transaction for Account.
-#[derive(Deserialize, Debug)]
+///
+/// Serialized in the format of the API with the amounts as strings in the
+/// currency of the transaction. Serializing fails if the account balance is
+/// not in the currency of the amount.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "TransactionData")]
 pub struct Transaction {
     /// Surrogate identifier for the transaction.
-    #[serde(rename = "transactionId")]
     pub transaction_id: String,
     /// Surrogate identifier for the account.
-    #[serde(rename = "accountId")]
     pub account_id: String,
     /// Archive ID of the transaction.
-    #[serde(rename = "archiveId")]
     pub archive_id: Option<String>,
     /// Reference number used in the transaction.
     pub reference: Option<String>,
     /// A message sent with the transaction. Created by the payer and comprises free-form text.
     pub message: Option<String>,
-    /// Amount transferred in the transaction. The value is a string decimal. Debit transactions
-    /// are marked with a minus sign.
-    pub amount: String,
-    /// Currency of the transaction.
-    pub currency: String,
-    /// Enum(debit, credit)
-    /// Describes whether the transaction is a debit of credit transaction.
-    #[serde(rename = "creditDebitIndicator")]
-    pub credit_debit_indicator: String,
-    /// Balance of the account after the transaction.
-    #[serde(rename = "accountBalance")]
-    pub account_balance: String,
+    /// Amount transferred in the transaction in the currency of the transaction. Debit
+    /// transactions are negative.
+    pub amount: Money,
+    /// Describes whether the transaction is a debit or credit transaction.
+    pub credit_debit_indicator: CreditDebitIndicator,
+    /// Balance of the account after the transaction. The API reports one
+    /// currency per transaction, which applies to both amounts.
+    pub account_balance: Money,
     /// Account information of the creditor. The response body will only contain this field if the
     /// transaction is of type debit, i.e. the counterparty in the transaction is the creditor.
     pub creditor: Option<TransactionParty>,
@@ -120,88 +410,325 @@ pub struct Transaction {
     /// transaction is of type credit, i.e. the counterparty in the transaction is the debtor.
     pub debtor: Option<TransactionParty>,
     /// Date and time the transaction was entered into book-keeping. ISO 8601-formatted date-time string.
-    #[serde(rename = "bookingDateTime")]
     pub booking_datetime: DateTime<Utc>,
     /// The date and time when amount of the transaction was counted towards the balance of the
     /// account. ISO 8601-formatted date-time string.
-    #[serde(rename = "valueDateTime")]
     pub value_datetaime: DateTime<Utc>,
-    /// Enum(Authorised, AwaitingAuthorisation, Rejected, Revoked)
     /// Current status of the transaction.
-    pub status: Option<String>,
+    pub status: Option<TransactionStatus>,
     /// ISO 20022-compliant transaction code for the transaction.
-    #[serde(rename = "isoTransactionCode")]
     pub iso_transaction_code: Option<String>,
     /// OP-specific transaction code for the transaction.
-    #[serde(rename = "opTransactionCode")]
     pub op_transaction_code: Option<String>,
 }
 
+/// Transaction as sent by the API, before the amounts are combined with the
+/// currency.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionData {
+    transaction_id: String,
+    account_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(with = "amount")]
+    amount: Decimal,
+    currency: Currency,
+    credit_debit_indicator: CreditDebitIndicator,
+    #[serde(with = "amount")]
+    account_balance: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    creditor: Option<TransactionParty>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    debtor: Option<TransactionParty>,
+    booking_date_time: DateTime<Utc>,
+    value_date_time: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<TransactionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iso_transaction_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    op_transaction_code: Option<String>,
+}
+
+impl From<TransactionData> for Transaction {
+    fn from(data: TransactionData) -> Transaction {
+        Transaction {
+            transaction_id: data.transaction_id,
+            account_id: data.account_id,
+            archive_id: data.archive_id,
+            reference: data.reference,
+            message: data.message,
+            amount: Money::new(data.amount, data.currency.clone()),
+            account_balance: Money::new(data.account_balance, data.currency),
+            credit_debit_indicator: data.credit_debit_indicator,
+            creditor: data.creditor,
+            debtor: data.debtor,
+            booking_datetime: data.booking_date_time,
+            value_datetaime: data.value_date_time,
+            status: data.status,
+            iso_transaction_code: data.iso_transaction_code,
+            op_transaction_code: data.op_transaction_code,
+        }
+    }
+}
+
+impl Serialize for Transaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let currency = self.amount.currency();
+        check_currency(currency, &self.account_balance).map_err(ser::Error::custom)?;
+        TransactionData {
+            transaction_id: self.transaction_id.clone(),
+            account_id: self.account_id.clone(),
+            archive_id: self.archive_id.clone(),
+            reference: self.reference.clone(),
+            message: self.message.clone(),
+            amount: self.amount.amount(),
+            currency: currency.clone(),
+            credit_debit_indicator: self.credit_debit_indicator.clone(),
+            account_balance: self.account_balance.amount(),
+            creditor: self.creditor.clone(),
+            debtor: self.debtor.clone(),
+            booking_date_time: self.booking_datetime,
+            value_date_time: self.value_datetaime,
+            status: self.status.clone(),
+            iso_transaction_code: self.iso_transaction_code.clone(),
+            op_transaction_code: self.op_transaction_code.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
 /// Describes links in the Transactions object.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TransactionListLinks {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub next: Option<Link>,
 }
 
 /// Describes a list of Transactions.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TransactionList {
     pub transactions: Vec<Transaction>,
     #[serde(rename = "_links")]
     pub links: TransactionListLinks,
 }
 
+/// Name of the API used for version overrides in Options.
+pub const API: &str = "accounts";
+
+/// Version of the API implemented by this client.
+pub const DEFAULT_VERSION: &str = "v3";
+
 /// Accounts client.
 ///
 /// This client is used to access the OP Accounts API.
+#[derive(Clone)]
 pub struct Accounts {
     options: Options,
+    version: String,
 }
 
 impl Accounts {
     /// Creates new Accounts client.
     ///
-    /// Bear in mind that this API is implemented to follow v3 so you must
-    /// specify v3 as version for the Options.
+    /// The client follows Accounts API v3 unless another version is set
+    /// for "accounts" in the Options.
     pub fn new(options: Options) -> Accounts {
-        Accounts { options }
+        let version = options
+            .api_version(API)
+            .unwrap_or(DEFAULT_VERSION)
+            .to_string();
+        Accounts { options, version }
+    }
+
+    /// Returns a copy of this client using given API version.
+    pub fn with_version(&self, version: &str) -> Accounts {
+        Accounts {
+            options: self.options.clone(),
+            version: version.to_string(),
+        }
+    }
+
+    /// Returns API version used by this client.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns a copy of this client using given timeout for whole requests.
+    ///
+    /// Can be used to override the timeout for single calls, for example
+    /// `client.with_timeout(Duration::from_secs(60)).transactions(id, None)`.
+    pub fn with_timeout(&self, timeout: Duration) -> Accounts {
+        let mut options = self.options.clone();
+        options.set_timeout(timeout);
+        Accounts {
+            options,
+            version: self.version.clone(),
+        }
+    }
+
+    /// Returns a copy of this client sending given x-request-id.
+    ///
+    /// By default a new request id is generated for each request.
+    pub fn with_request_id(&self, request_id: &str) -> Accounts {
+        let mut options = self.options.clone();
+        options.set_request_id(request_id);
+        Accounts {
+            options,
+            version: self.version.clone(),
+        }
     }
 
     /// Gets all accounts from the API and returns list of them.
-    pub async fn accounts(&self) -> Result<AccountList, Box<dyn Error>> {
-        let url = format!("/accounts/{}/accounts", self.options.version());
+    pub async fn accounts(&self) -> Result<ApiResponse<AccountList>> {
+        let url = format!("/accounts/{}/accounts", self.version);
         let response = Requests::get(&self.options, &url, None::<()>).await?;
         debug!("Accounts response: {:#?}", response);
-        let accounts: AccountList = response.json().await?;
-        Ok(accounts)
+        Requests::api_response(response)
     }
 
     /// Gets single account from the API based on accountId.
-    pub async fn account(&self, account_id: String) -> Result<Account, Box<dyn Error>> {
-        let url = format!(
-            "/accounts/{}/accounts/{}",
-            self.options.version(),
-            account_id
-        );
+    pub async fn account(&self, account_id: String) -> Result<ApiResponse<Account>> {
+        let url = format!("/accounts/{}/accounts/{}", self.version, account_id);
         let response = Requests::get(&self.options, &url, None::<()>).await?;
         debug!("Account response: {:#?}", response);
-        let account: Account = response.json().await?;
-        Ok(account)
+        Requests::api_response(response)
     }
 
     pub async fn transactions(
         &self,
         account_id: String,
         params: Option<TransactionParams>,
-    ) -> Result<TransactionList, Box<dyn Error>> {
+    ) -> Result<ApiResponse<TransactionList>> {
         let url = format!(
             "/accounts/{}/accounts/{}/transactions",
-            self.options.version(),
-            account_id
+            self.version, account_id
         );
         let response = Requests::get(&self.options, &url, params).await?;
         debug!("Transactions response: {:#?}", response);
-        let list: TransactionList = response.json().await?;
-        Ok(list)
+        Requests::api_response(response)
+    }
+
+    /// Returns stream of all transactions of the account.
+    ///
+    /// Pages are fetched as the stream is consumed by following the next
+    /// links, starting from the page selected by the params. The stream
+    /// ends after the first error.
+    pub fn transactions_stream(
+        &self,
+        account_id: String,
+        params: Option<TransactionParams>,
+    ) -> TransactionStream {
+        let state = Pages {
+            client: self.clone(),
+            account_id,
+            params: params.unwrap_or_default(),
+            buffer: VecDeque::new(),
+            done: false,
+        };
+        let inner = stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(transaction) = state.buffer.pop_front() {
+                    return Ok(Some((transaction, state)));
+                }
+                if state.done {
+                    return Ok(None);
+                }
+                state.fetch().await?;
+            }
+        });
+        TransactionStream {
+            inner: inner.boxed(),
+        }
+    }
+}
+
+/// Paging state of TransactionStream.
+struct Pages {
+    client: Accounts,
+    account_id: String,
+    params: TransactionParams,
+    buffer: VecDeque<Transaction>,
+    done: bool,
+}
+
+impl Pages {
+    /// Fetches the next page to the buffer.
+    async fn fetch(&mut self) -> Result<()> {
+        let page = self
+            .client
+            .transactions(self.account_id.clone(), Some(self.params.clone()))
+            .await?
+            .into_inner();
+        self.buffer.extend(page.transactions);
+        let token = match page.links.next {
+            Some(link) => paging_token(&link.href),
+            None => None,
+        };
+        match token {
+            // Same token again would never end
+            Some(token) if self.params.forward_paging_token.as_ref() != Some(&token) => {
+                self.params.forward_paging_token = Some(token);
+            }
+            Some(_) => {
+                warn!("Next link repeats the paging token, stopping");
+                self.done = true;
+            }
+            None => self.done = true,
+        }
+        Ok(())
+    }
+}
+
+/// Extracts forward paging token from the href of the next link.
+fn paging_token(href: &str) -> Option<String> {
+    let url = match Url::parse(href) {
+        Ok(url) => url,
+        // Links are usually relative to the API base URL
+        Err(_) => Url::parse("http://localhost").ok()?.join(href).ok()?,
+    };
+    let token = url
+        .query_pairs()
+        .find(|(name, _)| name == "forwardPagingToken")
+        .map(|(_, value)| value.into_owned());
+    if token.is_none() {
+        warn!("Next link has no paging token: {}", href);
+    }
+    token
+}
+
+/// Stream of transactions following the next links of the pages.
+///
+/// Created with Accounts::transactions_stream.
+pub struct TransactionStream {
+    inner: BoxStream<'static, Result<Transaction>>,
+}
+
+impl TransactionStream {
+    /// Limits the number of transactions in the stream.
+    ///
+    /// Pages after the limit has been reached are not fetched.
+    pub fn max_items(self, max_items: usize) -> TransactionStream {
+        TransactionStream {
+            inner: self.inner.take(max_items).boxed(),
+        }
+    }
+
+    /// Collects all transactions, failing on the first error.
+    pub async fn collect_all(self) -> Result<Vec<Transaction>> {
+        self.inner.try_collect().await
+    }
+}
+
+impl Stream for TransactionStream {
+    type Item = Result<Transaction>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
     }
 }
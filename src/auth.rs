@@ -0,0 +1,446 @@
+use crate::error::{Error, Result};
+use crate::redact::{redact_option, Redacted};
+use crate::token_store::TokenStore;
+use crate::transport::{HttpRequest, HttpTransport};
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, warn};
+use reqwest::header::{HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Tokens are refreshed this many seconds before they expire.
+const REFRESH_LEEWAY_SECS: i64 = 30;
+
+/// OAuth2 client configuration.
+///
+/// Client secret is redacted from the Debug output and zeroized when dropped.
+#[derive(Clone)]
+pub struct OAuthConfig {
+    token_url: String,
+    client_id: String,
+    client_secret: Option<Zeroizing<String>>,
+    scope: Option<String>,
+}
+
+impl OAuthConfig {
+    /// Creates new OAuthConfig for the given token endpoint and client id.
+    pub fn new(token_url: &str, client_id: &str) -> OAuthConfig {
+        OAuthConfig {
+            token_url: token_url.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: None,
+            scope: None,
+        }
+    }
+
+    /// Sets client secret sent to the token endpoint.
+    pub fn set_client_secret(&mut self, client_secret: &str) {
+        self.client_secret = Some(Zeroizing::new(client_secret.to_string()));
+    }
+
+    /// Sets space separated scopes requested for the tokens.
+    pub fn set_scope(&mut self, scope: &str) {
+        self.scope = Some(scope.to_string());
+    }
+
+    /// Returns URL of the token endpoint.
+    pub fn token_url(&self) -> &str {
+        &self.token_url
+    }
+
+    /// Returns client id.
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Returns requested scopes, if any.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+}
+
+impl fmt::Debug for OAuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuthConfig")
+            .field("token_url", &self.token_url)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redact_option(&self.client_secret))
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+/// OAuth2 grant used to obtain tokens.
+#[derive(Clone)]
+pub enum Grant {
+    /// Client credentials grant for application level access.
+    ClientCredentials,
+    /// Authorization code received from the end-user consent flow.
+    AuthorizationCode {
+        code: String,
+        redirect_uri: String,
+        code_verifier: Option<Zeroizing<String>>,
+    },
+    /// Refresh token from an earlier token response.
+    RefreshToken(Zeroizing<String>),
+}
+
+impl fmt::Debug for Grant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grant::ClientCredentials => f.write_str("ClientCredentials"),
+            Grant::AuthorizationCode { redirect_uri, .. } => f
+                .debug_struct("AuthorizationCode")
+                .field("code", &Redacted)
+                .field("redirect_uri", redirect_uri)
+                .finish(),
+            Grant::RefreshToken(_) => f.debug_tuple("RefreshToken").field(&Redacted).finish(),
+        }
+    }
+}
+
+/// Access token with optional refresh token and expiry.
+///
+/// Tokens are redacted from the Debug output and zeroized when dropped.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scope: Option<String>,
+}
+
+impl Token {
+    /// Creates new Token without refresh token or expiry.
+    pub fn new(access_token: &str) -> Token {
+        Token {
+            access_token: access_token.to_string(),
+            token_type: String::from("Bearer"),
+            refresh_token: None,
+            expires_at: None,
+            scope: None,
+        }
+    }
+
+    /// Returns true if the token has expired or expires within the leeway.
+    pub fn is_expired(&self, leeway: Duration) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at - leeway <= Utc::now(),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &Redacted)
+            .field("token_type", &self.token_type)
+            .field("refresh_token", &redact_option(&self.refresh_token))
+            .field("expires_at", &self.expires_at)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        self.access_token.zeroize();
+        self.refresh_token.zeroize();
+    }
+}
+
+/// Successful response from the token endpoint.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: Option<String>,
+    expires_in: Option<i64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+/// Error response from the token endpoint.
+#[derive(Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Requests a token from the token endpoint with the given grant.
+pub async fn request_token(
+    transport: &dyn HttpTransport,
+    config: &OAuthConfig,
+    grant: &Grant,
+) -> Result<Token> {
+    let mut form = vec![("client_id", config.client_id.as_str())];
+    if let Some(secret) = &config.client_secret {
+        form.push(("client_secret", secret.as_str()));
+    }
+    match grant {
+        Grant::ClientCredentials => {
+            form.push(("grant_type", "client_credentials"));
+            if let Some(scope) = &config.scope {
+                form.push(("scope", scope));
+            }
+        }
+        Grant::AuthorizationCode {
+            code,
+            redirect_uri,
+            code_verifier,
+        } => {
+            form.push(("grant_type", "authorization_code"));
+            form.push(("code", code));
+            form.push(("redirect_uri", redirect_uri));
+            if let Some(verifier) = code_verifier {
+                form.push(("code_verifier", verifier));
+            }
+        }
+        Grant::RefreshToken(refresh_token) => {
+            form.push(("grant_type", "refresh_token"));
+            form.push(("refresh_token", refresh_token));
+        }
+    }
+
+    let url = Url::parse(&config.token_url)
+        .map_err(|e| Error::Config(format!("invalid token URL {}: {}", config.token_url, e)))?;
+    let mut request = HttpRequest::new(Method::POST, url);
+    request
+        .headers
+        .insert(ACCEPT, HeaderValue::from_static("application/json"));
+    request.headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-www-form-urlencoded"),
+    );
+    let body = serde_urlencoded::to_string(&form)
+        .map_err(|e| Error::Config(format!("invalid token request: {}", e)))?;
+    request.body = Some(body.into_bytes());
+
+    debug!("Requesting token from {}", config.token_url);
+    let response = transport.send(request).await?;
+    let status = response.status;
+    let body = response.text();
+    if !status.is_success() {
+        return Err(match serde_json::from_str::<TokenError>(&body) {
+            Ok(e) => Error::Auth {
+                error: e.error,
+                description: e.error_description,
+                request_id: None,
+            },
+            Err(_) => Error::Auth {
+                error: format!("HTTP {}", status),
+                description: Some(body),
+                request_id: None,
+            },
+        });
+    }
+
+    let response: TokenResponse =
+        serde_json::from_str(&body).map_err(|source| Error::Deserialize {
+            source,
+            body,
+            request_id: None,
+        })?;
+    let refresh_token = match (response.refresh_token, grant) {
+        (Some(token), _) => Some(token),
+        // Refresh token is not always rotated, keep using the old one
+        (None, Grant::RefreshToken(old)) => Some(old.to_string()),
+        (None, _) => None,
+    };
+    Ok(Token {
+        access_token: response.access_token,
+        token_type: response
+            .token_type
+            .unwrap_or_else(|| String::from("Bearer")),
+        refresh_token,
+        expires_at: response
+            .expires_in
+            .map(|secs| Utc::now() + Duration::seconds(secs)),
+        scope: response.scope,
+    })
+}
+
+struct AuthState {
+    config: OAuthConfig,
+    client_credentials: bool,
+    token: Mutex<Option<Token>>,
+}
+
+/// TokenStore and the key the tokens are stored with.
+struct StoreEntry {
+    store: Arc<dyn TokenStore>,
+    key: String,
+}
+
+/// Provides access tokens for the requests.
+///
+/// Caches the access token and refreshes it before it expires, either with
+/// the refresh token or by requesting a new one with client credentials.
+/// Clones share the same token. Refreshed tokens are saved to the
+/// TokenStore if one is set.
+#[derive(Clone)]
+pub struct Authenticator {
+    state: Arc<AuthState>,
+    store: Option<Arc<StoreEntry>>,
+}
+
+impl fmt::Debug for Authenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Authenticator")
+            .field("config", &self.state.config)
+            .field("client_credentials", &self.state.client_credentials)
+            .field("store", &self.store.as_ref().map(|entry| &entry.key))
+            .finish()
+    }
+}
+
+impl Authenticator {
+    /// Creates Authenticator using the client credentials grant.
+    pub fn client_credentials(config: OAuthConfig) -> Authenticator {
+        Authenticator::create(config, true, None)
+    }
+
+    /// Creates Authenticator from an existing token, for example from the
+    /// authorization code flow. The token is refreshed with its refresh
+    /// token when it expires.
+    pub fn with_token(config: OAuthConfig, token: Token) -> Authenticator {
+        Authenticator::create(config, false, Some(token))
+    }
+
+    /// Creates Authenticator from a token saved earlier to the store.
+    ///
+    /// The token is refreshed with its refresh token when it expires and
+    /// the new token is saved back to the store.
+    pub fn from_store(
+        config: OAuthConfig,
+        store: Arc<dyn TokenStore>,
+        key: &str,
+    ) -> Result<Authenticator> {
+        let token = store.load(key)?.ok_or_else(|| Error::Auth {
+            error: String::from("token_not_found"),
+            description: Some(format!("no token stored for {}", key)),
+            request_id: None,
+        })?;
+        let mut authenticator = Authenticator::create(config, false, Some(token));
+        authenticator.store = Some(Arc::new(StoreEntry {
+            store,
+            key: key.to_string(),
+        }));
+        Ok(authenticator)
+    }
+
+    fn create(
+        config: OAuthConfig,
+        client_credentials: bool,
+        token: Option<Token>,
+    ) -> Authenticator {
+        Authenticator {
+            state: Arc::new(AuthState {
+                config,
+                client_credentials,
+                token: Mutex::new(token),
+            }),
+            store: None,
+        }
+    }
+
+    /// Uses the store for persisting tokens with the given key.
+    ///
+    /// If this Authenticator already has a token it is saved to the store,
+    /// otherwise a token is loaded from the store if available.
+    pub async fn with_store(
+        mut self,
+        store: Arc<dyn TokenStore>,
+        key: &str,
+    ) -> Result<Authenticator> {
+        {
+            let mut token = self.state.token.lock().await;
+            match token.as_ref() {
+                Some(t) => store.save(key, t)?,
+                None => *token = store.load(key)?,
+            }
+        }
+        self.store = Some(Arc::new(StoreEntry {
+            store,
+            key: key.to_string(),
+        }));
+        Ok(self)
+    }
+
+    /// Returns OAuth configuration.
+    pub fn config(&self) -> &OAuthConfig {
+        &self.state.config
+    }
+
+    /// Returns the currently cached token, if any.
+    pub async fn token(&self) -> Option<Token> {
+        self.state.token.lock().await.clone()
+    }
+
+    /// Returns a valid access token, refreshing it first if needed.
+    pub async fn access_token(&self, transport: &dyn HttpTransport) -> Result<String> {
+        let mut token = self.state.token.lock().await;
+        if let Some(t) = token.as_ref() {
+            if !t.is_expired(Duration::seconds(REFRESH_LEEWAY_SECS)) {
+                return Ok(t.access_token.clone());
+            }
+        }
+        let fresh = self.fetch(transport, token.as_ref()).await?;
+        let access_token = fresh.access_token.clone();
+        *token = Some(fresh);
+        Ok(access_token)
+    }
+
+    /// Refreshes the token after the API rejected the given access token.
+    ///
+    /// If another request already refreshed the token, that one is used.
+    pub async fn refresh(&self, transport: &dyn HttpTransport, rejected: &str) -> Result<String> {
+        let mut token = self.state.token.lock().await;
+        if let Some(t) = token.as_ref() {
+            if t.access_token != rejected {
+                return Ok(t.access_token.clone());
+            }
+        }
+        let fresh = self.fetch(transport, token.as_ref()).await?;
+        let access_token = fresh.access_token.clone();
+        *token = Some(fresh);
+        Ok(access_token)
+    }
+
+    async fn fetch(&self, transport: &dyn HttpTransport, current: Option<&Token>) -> Result<Token> {
+        let token = self.request(transport, current).await?;
+        if let Some(entry) = &self.store {
+            // Request can still succeed, so failing to persist is only logged
+            if let Err(e) = entry.store.save(&entry.key, &token) {
+                warn!("Failed to save refreshed token: {}", e);
+            }
+        }
+        Ok(token)
+    }
+
+    async fn request(
+        &self,
+        transport: &dyn HttpTransport,
+        current: Option<&Token>,
+    ) -> Result<Token> {
+        let grant = match current.and_then(|t| t.refresh_token.clone()) {
+            Some(refresh_token) => Grant::RefreshToken(Zeroizing::new(refresh_token)),
+            None if self.state.client_credentials => Grant::ClientCredentials,
+            None => {
+                return Err(Error::Auth {
+                    error: String::from("token_expired"),
+                    description: Some(String::from(
+                        "access token expired and no refresh token is available",
+                    )),
+                    request_id: None,
+                })
+            }
+        };
+        request_token(transport, &self.state.config, &grant).await
+    }
+}
@@ -0,0 +1,275 @@
+use crate::auth::{request_token, Authenticator, Grant, OAuthConfig, Token};
+use crate::error::{Error, Result};
+use crate::redact::Redacted;
+use crate::request_object::{RequestObject, RequestObjectSigner};
+use crate::transport::HttpTransport;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// Length of the generated PKCE code verifier. Allowed range is 43-128.
+const VERIFIER_LENGTH: usize = 64;
+
+/// Length of the generated state parameter.
+const STATE_LENGTH: usize = 32;
+
+/// PKCE code verifier and its S256 challenge.
+///
+/// Verifier is redacted from the Debug output and zeroized when dropped.
+#[derive(Clone)]
+pub struct Pkce {
+    pub verifier: Zeroizing<String>,
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// Generates a new random code verifier.
+    pub fn new() -> Pkce {
+        Pkce::from_verifier(&random_string(VERIFIER_LENGTH))
+    }
+
+    /// Creates Pkce from an existing code verifier.
+    pub fn from_verifier(verifier: &str) -> Pkce {
+        let digest = Sha256::digest(verifier.as_bytes());
+        Pkce {
+            verifier: Zeroizing::new(verifier.to_string()),
+            challenge: base64::encode_config(digest, base64::URL_SAFE_NO_PAD),
+        }
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Pkce {
+        Pkce::new()
+    }
+}
+
+impl fmt::Debug for Pkce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkce")
+            .field("verifier", &Redacted)
+            .field("challenge", &self.challenge)
+            .finish()
+    }
+}
+
+/// Pending authorization started with AuthorizationFlow::start.
+///
+/// State and code verifier must be kept, for example in the user session,
+/// until the end-user returns to the redirect URI.
+#[derive(Clone)]
+pub struct AuthorizationRequest {
+    /// URL the end-user is redirected to for giving consent.
+    pub url: String,
+    /// Random state that must match the state in the callback.
+    pub state: String,
+    /// PKCE code verifier sent when exchanging the code, zeroized when
+    /// dropped.
+    pub code_verifier: Zeroizing<String>,
+}
+
+impl fmt::Debug for AuthorizationRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthorizationRequest")
+            .field("url", &self.url)
+            .field("state", &self.state)
+            .field("code_verifier", &Redacted)
+            .finish()
+    }
+}
+
+/// Authorization code flow with PKCE for end-user consent.
+///
+/// Example:
+///
+/// ```
+/// use op_api_sdk::auth::OAuthConfig;
+/// use op_api_sdk::authorization::AuthorizationFlow;
+///
+/// let config = OAuthConfig::new("https://example.com/oauth/token", "client");
+/// let flow = AuthorizationFlow::new(
+///     "https://example.com/oauth/authorize",
+///     config,
+///     "https://my.app/callback",
+/// );
+/// let request = flow.start().unwrap();
+/// assert!(request.url.contains("code_challenge_method=S256"));
+/// ```
+#[derive(Clone)]
+pub struct AuthorizationFlow {
+    authorize_url: String,
+    redirect_uri: String,
+    config: OAuthConfig,
+    signer: Option<RequestObjectSigner>,
+    authorization_id: Option<String>,
+}
+
+impl AuthorizationFlow {
+    /// Creates new AuthorizationFlow.
+    pub fn new(authorize_url: &str, config: OAuthConfig, redirect_uri: &str) -> AuthorizationFlow {
+        AuthorizationFlow {
+            authorize_url: authorize_url.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            config,
+            signer: None,
+            authorization_id: None,
+        }
+    }
+
+    /// Sends the authorization parameters also as a signed request object.
+    ///
+    /// Required by the PSD2 APIs.
+    pub fn set_request_signer(&mut self, signer: RequestObjectSigner) {
+        self.signer = Some(signer);
+    }
+
+    /// Sets authorizationId of the consent that the end-user authorizes.
+    ///
+    /// Only used in the signed request object.
+    pub fn set_authorization_id(&mut self, authorization_id: &str) {
+        self.authorization_id = Some(authorization_id.to_string());
+    }
+
+    /// Returns OAuth configuration.
+    pub fn config(&self) -> &OAuthConfig {
+        &self.config
+    }
+
+    /// Starts authorization by generating state, PKCE verifier and the
+    /// authorization URL.
+    pub fn start(&self) -> Result<AuthorizationRequest> {
+        self.start_with(&random_string(STATE_LENGTH), &Pkce::new())
+    }
+
+    /// Starts authorization with given state and PKCE verifier.
+    pub fn start_with(&self, state: &str, pkce: &Pkce) -> Result<AuthorizationRequest> {
+        let mut url = Url::parse(&self.authorize_url).map_err(|e| {
+            Error::Config(format!(
+                "invalid authorization URL {}: {}",
+                self.authorize_url, e
+            ))
+        })?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", self.config.client_id())
+                .append_pair("redirect_uri", &self.redirect_uri)
+                .append_pair("state", state)
+                .append_pair("code_challenge", &pkce.challenge)
+                .append_pair("code_challenge_method", "S256");
+            if let Some(scope) = self.config.scope() {
+                query.append_pair("scope", scope);
+            }
+            if let Some(signer) = &self.signer {
+                query.append_pair("request", &self.request_object(signer, state, pkce)?);
+            }
+        }
+        Ok(AuthorizationRequest {
+            url: url.to_string(),
+            state: state.to_string(),
+            code_verifier: pkce.verifier.clone(),
+        })
+    }
+
+    fn request_object(
+        &self,
+        signer: &RequestObjectSigner,
+        state: &str,
+        pkce: &Pkce,
+    ) -> Result<String> {
+        let mut request = RequestObject::new(
+            self.config.client_id(),
+            signer.audience(),
+            &self.redirect_uri,
+            state,
+            &random_string(STATE_LENGTH),
+        );
+        request.scope = self.config.scope().map(String::from);
+        request.nonce = Some(random_string(STATE_LENGTH));
+        request.code_challenge = Some(pkce.challenge.clone());
+        request.code_challenge_method = Some(String::from("S256"));
+        if let Some(authorization_id) = &self.authorization_id {
+            request.set_authorization_id(authorization_id);
+        }
+        signer.sign(&request)
+    }
+
+    /// Validates the callback URL and returns the authorization code.
+    ///
+    /// Fails if the state does not match or the end-user denied consent.
+    pub fn parse_callback(
+        &self,
+        request: &AuthorizationRequest,
+        callback_url: &str,
+    ) -> Result<String> {
+        let url =
+            Url::parse(callback_url).map_err(|e| auth_error("invalid_callback", &e.to_string()))?;
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        if param("state").as_deref() != Some(request.state.as_str()) {
+            return Err(auth_error(
+                "invalid_state",
+                "state in the callback does not match the request",
+            ));
+        }
+        if let Some(error) = param("error") {
+            return Err(Error::Auth {
+                error,
+                description: param("error_description"),
+                request_id: None,
+            });
+        }
+        param("code").ok_or_else(|| auth_error("invalid_callback", "code is missing"))
+    }
+
+    /// Exchanges the authorization code for tokens.
+    pub async fn exchange_code(
+        &self,
+        transport: &dyn HttpTransport,
+        request: &AuthorizationRequest,
+        code: &str,
+    ) -> Result<Token> {
+        let grant = Grant::AuthorizationCode {
+            code: code.to_string(),
+            redirect_uri: self.redirect_uri.clone(),
+            code_verifier: Some(request.code_verifier.clone()),
+        };
+        request_token(transport, &self.config, &grant).await
+    }
+
+    /// Validates the callback and exchanges the code for an Authenticator
+    /// that can be set to Options.
+    pub async fn finish(
+        &self,
+        transport: &dyn HttpTransport,
+        request: &AuthorizationRequest,
+        callback_url: &str,
+    ) -> Result<Authenticator> {
+        let code = self.parse_callback(request, callback_url)?;
+        let token = self.exchange_code(transport, request, &code).await?;
+        Ok(Authenticator::with_token(self.config.clone(), token))
+    }
+}
+
+fn auth_error(error: &str, description: &str) -> Error {
+    Error::Auth {
+        error: error.to_string(),
+        description: Some(description.to_string()),
+        request_id: None,
+    }
+}
+
+/// Generates random string from URL safe characters.
+fn random_string(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .collect()
+}
@@ -0,0 +1,114 @@
+use super::Runtime;
+use crate::apis::accounts::{
+    self, Account, AccountList, Transaction, TransactionList, TransactionParams, TransactionStream,
+};
+use crate::error::Result;
+use crate::options::Options;
+use crate::response::ApiResponse;
+use futures::StreamExt;
+use std::time::Duration;
+
+/// Blocking Accounts client.
+///
+/// Synchronous equivalent of apis::accounts::Accounts.
+#[derive(Clone)]
+pub struct Accounts {
+    inner: accounts::Accounts,
+    runtime: Runtime,
+}
+
+impl Accounts {
+    /// Creates new blocking Accounts client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime for the requests cannot be created.
+    pub fn new(options: Options) -> Accounts {
+        Accounts {
+            inner: accounts::Accounts::new(options),
+            runtime: Runtime::new(),
+        }
+    }
+
+    /// Returns a copy of this client using given API version.
+    pub fn with_version(&self, version: &str) -> Accounts {
+        Accounts {
+            inner: self.inner.with_version(version),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Returns API version used by this client.
+    pub fn version(&self) -> &str {
+        self.inner.version()
+    }
+
+    /// Returns a copy of this client using given timeout for whole requests.
+    pub fn with_timeout(&self, timeout: Duration) -> Accounts {
+        Accounts {
+            inner: self.inner.with_timeout(timeout),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Returns a copy of this client sending given x-request-id.
+    pub fn with_request_id(&self, request_id: &str) -> Accounts {
+        Accounts {
+            inner: self.inner.with_request_id(request_id),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Gets all accounts from the API and returns list of them.
+    pub fn accounts(&self) -> Result<ApiResponse<AccountList>> {
+        self.runtime.block_on(self.inner.accounts())
+    }
+
+    /// Gets single account from the API based on accountId.
+    pub fn account(&self, account_id: String) -> Result<ApiResponse<Account>> {
+        self.runtime.block_on(self.inner.account(account_id))
+    }
+
+    /// Gets transactions of the account.
+    pub fn transactions(
+        &self,
+        account_id: String,
+        params: Option<TransactionParams>,
+    ) -> Result<ApiResponse<TransactionList>> {
+        self.runtime
+            .block_on(self.inner.transactions(account_id, params))
+    }
+
+    /// Returns iterator over all transactions of the account.
+    ///
+    /// Synchronous equivalent of Accounts::transactions_stream. Pages are
+    /// fetched as the iterator is consumed, so `take` limits the number of
+    /// requests as well.
+    pub fn transactions_iter(
+        &self,
+        account_id: String,
+        params: Option<TransactionParams>,
+    ) -> TransactionIter {
+        TransactionIter {
+            stream: self.inner.transactions_stream(account_id, params),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Iterator over transactions following the next links of the pages.
+///
+/// Created with Accounts::transactions_iter.
+pub struct TransactionIter {
+    stream: TransactionStream,
+    runtime: Runtime,
+}
+
+impl Iterator for TransactionIter {
+    type Item = Result<Transaction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream = &mut self.stream;
+        self.runtime.block_on(stream.next())
+    }
+}
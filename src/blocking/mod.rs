@@ -0,0 +1,34 @@
+mod accounts;
+
+pub use accounts::{Accounts, TransactionIter};
+
+use std::future::Future;
+use std::sync::Arc;
+
+/// Runtime the blocking clients run the requests on.
+///
+/// Clones share the same runtime and thus the same worker thread.
+#[derive(Clone)]
+pub(crate) struct Runtime(Arc<tokio::runtime::Runtime>);
+
+impl Runtime {
+    /// Creates new Runtime with a single worker thread.
+    ///
+    /// Connections of the HTTP client are driven by the worker, so they can
+    /// be reused between calls.
+    pub(crate) fn new() -> Runtime {
+        let runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .core_threads(1)
+            .thread_name("op-api-sdk-blocking")
+            .enable_all()
+            .build()
+            .expect("failed to create runtime for blocking client");
+        Runtime(Arc::new(runtime))
+    }
+
+    /// Runs the future to completion, blocking the current thread.
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0.handle().block_on(future)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::{Error, Result};
+use crate::options::{Environment, Options, OptionsBuilder};
+use crate::redact::redact_option;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Environment variable for the API key. Required.
+pub const ENV_API_KEY: &str = "X_API_KEY";
+/// Environment variable for the access token without 'Bearer'.
+pub const ENV_AUTHORIZATION: &str = "OP_AUTHORIZATION";
+/// Environment variable for the environment, "sandbox" (default) or "production".
+pub const ENV_ENVIRONMENT: &str = "OP_ENVIRONMENT";
+/// Environment variable for a custom base URL. Overrides the environment.
+pub const ENV_BASE_URL: &str = "OP_BASE_URL";
+/// Environment variable for per-API versions, for example "accounts=v3,mobility=v1".
+pub const ENV_API_VERSIONS: &str = "OP_API_VERSIONS";
+/// Environment variable for the whole request timeout in seconds.
+pub const ENV_TIMEOUT: &str = "OP_TIMEOUT_SECS";
+/// Environment variable for the connect timeout in seconds.
+pub const ENV_CONNECT_TIMEOUT: &str = "OP_CONNECT_TIMEOUT_SECS";
+
+/// Serializable configuration for Options.
+///
+/// Can be read from environment variables or from a TOML or JSON file:
+///
+/// ```toml
+/// api_key = "X_API_KEY"
+/// environment = "sandbox"
+/// timeout_secs = 30
+///
+/// [api_versions]
+/// accounts = "v3"
+/// ```
+///
+/// API key and authorization are redacted from the Debug output and
+/// zeroized when dropped.
+#[derive(Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub api_key: Option<Zeroizing<String>>,
+    pub authorization: Option<Zeroizing<String>>,
+    pub environment: Option<String>,
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub api_versions: HashMap<String, String>,
+    pub user_agent: Option<String>,
+    pub proxy: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl Config {
+    /// Reads configuration from the environment variables.
+    ///
+    /// See the ENV_* constants for the variable names.
+    pub fn from_env() -> Result<Config> {
+        Config::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Result<Config> {
+        let api_key = var(ENV_API_KEY)
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| {
+                Error::Config(format!("environment variable {} is not set", ENV_API_KEY))
+            })?;
+        let api_versions = match var(ENV_API_VERSIONS) {
+            Some(versions) => parse_api_versions(&versions)?,
+            None => HashMap::new(),
+        };
+        Ok(Config {
+            api_key: Some(Zeroizing::new(api_key)),
+            authorization: var(ENV_AUTHORIZATION).map(Zeroizing::new),
+            environment: var(ENV_ENVIRONMENT),
+            base_url: var(ENV_BASE_URL),
+            api_versions,
+            user_agent: None,
+            proxy: None,
+            timeout_secs: parse_secs(ENV_TIMEOUT, var(ENV_TIMEOUT))?,
+            connect_timeout_secs: parse_secs(ENV_CONNECT_TIMEOUT, var(ENV_CONNECT_TIMEOUT))?,
+        })
+    }
+
+    /// Reads configuration from a TOML or JSON file.
+    ///
+    /// Format is selected by the file extension.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Config> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("failed to read {}: {}", path.display(), e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content)
+                .map_err(|e| Error::Config(format!("invalid {}: {}", path.display(), e))),
+            Some("json") => serde_json::from_str(&content)
+                .map_err(|e| Error::Config(format!("invalid {}: {}", path.display(), e))),
+            _ => Err(Error::Config(format!(
+                "unsupported config file format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Converts configuration to OptionsBuilder for further configuration.
+    pub fn into_builder(self) -> Result<OptionsBuilder> {
+        let mut builder = Options::builder();
+        if let Some(api_key) = self.api_key {
+            builder = builder.api_key(&api_key);
+        }
+        if let Some(authorization) = self.authorization {
+            builder = builder.authorization(&authorization);
+        }
+        if let Some(environment) = self.environment {
+            builder = builder.environment(environment.parse::<Environment>()?);
+        }
+        if let Some(base_url) = self.base_url {
+            builder = builder.base_url(&base_url);
+        }
+        for (api, version) in self.api_versions.iter() {
+            builder = builder.api_version(api, version);
+        }
+        if let Some(user_agent) = self.user_agent {
+            builder = builder.user_agent(&user_agent);
+        }
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(&proxy);
+        }
+        if let Some(secs) = self.timeout_secs {
+            builder = builder.timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+        Ok(builder)
+    }
+
+    /// Validates the configuration and builds Options.
+    pub fn build(self) -> Result<Options> {
+        self.into_builder()?.build()
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("api_key", &redact_option(&self.api_key))
+            .field("authorization", &redact_option(&self.authorization))
+            .field("environment", &self.environment)
+            .field("base_url", &self.base_url)
+            .field("api_versions", &self.api_versions)
+            .field("user_agent", &self.user_agent)
+            .field("proxy", &self.proxy)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("connect_timeout_secs", &self.connect_timeout_secs)
+            .finish()
+    }
+}
+
+/// Parses per-API versions in format "accounts=v3,mobility=v1".
+fn parse_api_versions(value: &str) -> Result<HashMap<String, String>> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let mut parts = entry.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(api), Some(version))
+                    if !api.trim().is_empty() && !version.trim().is_empty() =>
+                {
+                    Ok((api.trim().to_string(), version.trim().to_string()))
+                }
+                _ => Err(Error::Config(format!(
+                    "invalid {} entry: {}",
+                    ENV_API_VERSIONS, entry
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Parses number of seconds from an environment variable.
+fn parse_secs(name: &str, value: Option<String>) -> Result<Option<u64>> {
+    match value {
+        Some(v) => v
+            .trim()
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|_| Error::Config(format!("invalid {}: {}", name, v))),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,258 @@
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Header carrying the id of the request.
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Result type used by all clients in this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Single error from OP API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub id: String,
+    pub level: String,
+    pub r#type: String,
+    pub message: String,
+}
+
+/// Container for API errors from OP API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiErrors {
+    pub errors: Vec<ApiError>,
+}
+
+/// Implement functionality to display ApiErrors.
+impl fmt::Display for ApiErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "API error occurred, reasons: {:?}", self.errors)
+    }
+}
+
+/// Implement std::error::Error for ApiErrors.
+impl std::error::Error for ApiErrors {}
+
+/// Failure to send a request or to read its response.
+///
+/// Wraps the error from the HTTP transport.
+#[derive(Debug)]
+pub struct TransportError {
+    connect: bool,
+    source: Box<dyn std::error::Error + Send + Sync>,
+    request_id: Option<String>,
+}
+
+impl TransportError {
+    /// Creates new TransportError from the error of the transport.
+    pub fn new<E: Into<Box<dyn std::error::Error + Send + Sync>>>(source: E) -> TransportError {
+        TransportError {
+            connect: false,
+            source: source.into(),
+            request_id: None,
+        }
+    }
+
+    /// Creates new TransportError for a connection that could not be
+    /// established. These are retried as the request was never sent.
+    pub fn connect<E: Into<Box<dyn std::error::Error + Send + Sync>>>(source: E) -> TransportError {
+        TransportError {
+            connect: true,
+            source: source.into(),
+            request_id: None,
+        }
+    }
+
+    /// Returns true if the connection could not be established.
+    pub fn is_connect(&self) -> bool {
+        self.connect
+    }
+
+    /// Returns the error of the transport.
+    pub fn get_ref(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.source.as_ref()
+    }
+
+    /// Returns id of the failed request, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Errors returned by the SDK.
+#[derive(Debug)]
+pub enum Error {
+    /// Request could not be sent or response could not be read.
+    Transport(TransportError),
+    /// Request did not complete within the configured timeout.
+    Timeout(TransportError),
+    /// API responded with an unsuccessful HTTP status.
+    ///
+    /// Errors are only available if the body is a valid ApiErrors document.
+    Api {
+        status: StatusCode,
+        headers: Box<HeaderMap>,
+        body: String,
+        errors: Option<ApiErrors>,
+    },
+    /// Response body could not be deserialized. Raw body is attached.
+    Deserialize {
+        source: serde_json::Error,
+        body: String,
+        request_id: Option<String>,
+    },
+    /// Options are missing or contain invalid values.
+    Config(String),
+    /// Token could not be loaded from or saved to a TokenStore.
+    TokenStore(String),
+    /// Access token could not be obtained from the token endpoint.
+    ///
+    /// Request id is set when the token was needed for an API request.
+    Auth {
+        error: String,
+        description: Option<String>,
+        request_id: Option<String>,
+    },
+}
+
+impl Error {
+    /// Returns the HTTP status if the error originates from the API response.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Returns the response headers if the error originates from the API response.
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        match self {
+            Error::Api { headers, .. } => Some(headers),
+            _ => None,
+        }
+    }
+
+    /// Returns the delay requested by the API with Retry-After header.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let value = self.headers()?.get(RETRY_AFTER)?.to_str().ok()?;
+        crate::retry::parse_retry_after(value)
+    }
+
+    /// Returns id of the request the error originates from.
+    ///
+    /// This is the x-request-id returned by the API or, if the API did not
+    /// return one, the id sent with the request.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::Transport(e) | Error::Timeout(e) => e.request_id(),
+            Error::Api { headers, .. } => headers.get(REQUEST_ID_HEADER)?.to_str().ok(),
+            Error::Deserialize { request_id, .. } | Error::Auth { request_id, .. } => {
+                request_id.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Attaches id of the request to the error unless it already has one.
+    pub(crate) fn with_request_id(mut self, request_id: &str) -> Error {
+        match &mut self {
+            Error::Transport(e) | Error::Timeout(e) if e.request_id.is_none() => {
+                e.request_id = Some(request_id.to_string());
+            }
+            Error::Api { headers, .. } if !headers.contains_key(REQUEST_ID_HEADER) => {
+                if let Ok(value) = HeaderValue::from_str(request_id) {
+                    headers.insert(REQUEST_ID_HEADER, value);
+                }
+            }
+            Error::Deserialize {
+                request_id: id @ None,
+                ..
+            }
+            | Error::Auth {
+                request_id: id @ None,
+                ..
+            } => *id = Some(request_id.to_string()),
+            _ => {}
+        }
+        self
+    }
+
+    /// Returns the raw response body if available.
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            Error::Api { body, .. } => Some(body),
+            Error::Deserialize { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Transport(e) => write!(f, "Transport error: {}", e)?,
+            Error::Timeout(e) => write!(f, "Request timed out: {}", e)?,
+            Error::Api {
+                status,
+                errors: Some(errors),
+                ..
+            } => write!(f, "HTTP {}: {}", status, errors)?,
+            Error::Api { status, body, .. } => write!(f, "HTTP {}: {}", status, body)?,
+            Error::Deserialize { source, .. } => {
+                write!(f, "Failed to deserialize response: {}", source)?
+            }
+            Error::Config(msg) => write!(f, "Invalid configuration: {}", msg)?,
+            Error::TokenStore(msg) => write!(f, "Token store error: {}", msg)?,
+            Error::Auth {
+                error,
+                description: Some(description),
+                ..
+            } => write!(f, "Authentication failed: {} ({})", error, description)?,
+            Error::Auth { error, .. } => write!(f, "Authentication failed: {}", error)?,
+        }
+        match self.request_id() {
+            Some(request_id) => write!(f, " [request id {}]", request_id),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(e) | Error::Timeout(e) => Some(e),
+            Error::Api {
+                errors: Some(errors),
+                ..
+            } => Some(errors),
+            Error::Deserialize { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Error::Timeout(TransportError::new(e))
+        } else if e.is_connect() {
+            Error::Transport(TransportError::connect(e))
+        } else {
+            Error::Transport(TransportError::new(e))
+        }
+    }
+}
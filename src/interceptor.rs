@@ -0,0 +1,162 @@
+use crate::error::{Error, Result};
+use crate::redact::Headers;
+use crate::transport::{HttpRequest, HttpResponse};
+use log::{log, Level};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::fmt;
+
+/// Hook into the request pipeline of the clients.
+///
+/// Interceptors are called for every attempt of a request, in the order
+/// they were added to Options before the request is sent and in reverse
+/// order after the response has been received. Unsuccessful statuses are
+/// passed to after_response before they are turned into errors.
+pub trait Interceptor: Send + Sync {
+    /// Inspects or modifies the request before it is sent.
+    ///
+    /// Returning an error aborts the request.
+    fn before_request(&self, _request: &mut HttpRequest) -> Result<()> {
+        Ok(())
+    }
+
+    /// Inspects or modifies the response of the request.
+    ///
+    /// Returning an error fails the request with that error.
+    fn after_response(&self, _request: &HttpRequest, _response: &mut HttpResponse) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the request failed without a response, for example
+    /// because of a connection error or timeout.
+    fn on_error(&self, _request: &HttpRequest, _error: &Error) {}
+}
+
+/// Interceptor logging the requests and responses.
+///
+/// Credentials are masked in the logged headers.
+#[derive(Clone, Debug)]
+pub struct LoggingInterceptor {
+    level: Level,
+    headers: bool,
+}
+
+impl LoggingInterceptor {
+    /// Creates new LoggingInterceptor logging with given level.
+    pub fn new(level: Level) -> LoggingInterceptor {
+        LoggingInterceptor {
+            level,
+            headers: false,
+        }
+    }
+
+    /// Sets whether headers are logged as well.
+    pub fn set_headers(&mut self, headers: bool) {
+        self.headers = headers;
+    }
+}
+
+impl Default for LoggingInterceptor {
+    fn default() -> LoggingInterceptor {
+        LoggingInterceptor::new(Level::Info)
+    }
+}
+
+impl Interceptor for LoggingInterceptor {
+    fn before_request(&self, request: &mut HttpRequest) -> Result<()> {
+        if self.headers {
+            log!(
+                self.level,
+                "--> {} {} {:?}",
+                request.method,
+                request.url,
+                Headers(&request.headers)
+            );
+        } else {
+            log!(self.level, "--> {} {}", request.method, request.url);
+        }
+        Ok(())
+    }
+
+    fn after_response(&self, request: &HttpRequest, response: &mut HttpResponse) -> Result<()> {
+        if self.headers {
+            log!(
+                self.level,
+                "<-- {} {} {} {:?}",
+                response.status,
+                request.method,
+                request.url,
+                Headers(&response.headers)
+            );
+        } else {
+            log!(
+                self.level,
+                "<-- {} {} {}",
+                response.status,
+                request.method,
+                request.url
+            );
+        }
+        Ok(())
+    }
+
+    fn on_error(&self, request: &HttpRequest, error: &Error) {
+        log!(
+            self.level,
+            "<-- {} {} failed: {}",
+            request.method,
+            request.url,
+            error
+        );
+    }
+}
+
+/// Interceptor adding fixed headers to every request.
+///
+/// Existing headers with the same name are replaced.
+#[derive(Clone, Default)]
+pub struct HeaderInterceptor {
+    headers: HeaderMap,
+}
+
+impl HeaderInterceptor {
+    /// Creates new HeaderInterceptor without headers.
+    pub fn new() -> HeaderInterceptor {
+        HeaderInterceptor::default()
+    }
+
+    /// Sets header added to the requests.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<()> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| Error::Config(format!("invalid header name {}: {}", name, e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| Error::Config(format!("invalid value for header {}: {}", name, e)))?;
+        self.headers.insert(name, value);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for HeaderInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeaderInterceptor")
+            .field("headers", &Headers(&self.headers))
+            .finish()
+    }
+}
+
+impl From<HeaderMap> for HeaderInterceptor {
+    fn from(headers: HeaderMap) -> HeaderInterceptor {
+        HeaderInterceptor { headers }
+    }
+}
+
+impl Interceptor for HeaderInterceptor {
+    fn before_request(&self, request: &mut HttpRequest) -> Result<()> {
+        for name in self.headers.keys() {
+            request.headers.remove(name);
+        }
+        for (name, value) in self.headers.iter() {
+            request.headers.append(name, value.clone());
+        }
+        Ok(())
+    }
+}
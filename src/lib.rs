@@ -2,7 +2,29 @@
 ///
 /// See apis crate for all clients available.
 pub mod apis;
+pub mod auth;
+pub mod authorization;
+/// Synchronous clients, enabled with the blocking feature.
+///
+/// The clients wrap the async clients and run their requests on an
+/// internal runtime, so they share the models, Options and errors with
+/// them. Blocking clients must not be used from within an async runtime.
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod config;
+pub mod error;
+pub mod interceptor;
+pub mod money;
 pub mod options;
-mod requests;
+pub mod rate_limit;
+mod redact;
+pub mod request_object;
+pub mod requests;
+pub mod response;
+pub mod retry;
+pub mod tls;
+pub mod token_store;
+pub mod transport;
 
 pub use apis::*;
+pub use error::{Error, Result};
@@ -0,0 +1,337 @@
+//! Exact monetary amounts used by the API models.
+//!
+//! Amounts are kept as decimals so that balances and transaction amounts
+//! can be summed and compared without floating point rounding errors.
+use crate::error::{Error, Result};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+pub use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Grouping separator used in formatted amounts (no-break space).
+const GROUP_SEPARATOR: char = '\u{a0}';
+
+/// ISO 4217 currency code.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency(String);
+
+impl Currency {
+    /// Euro, the currency of nearly all OP accounts.
+    pub fn eur() -> Currency {
+        Currency(String::from("EUR"))
+    }
+
+    /// Creates new Currency from three letter ISO 4217 code.
+    ///
+    /// Lowercase codes are accepted and converted to uppercase.
+    pub fn new(code: &str) -> Result<Currency> {
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::Config(format!("invalid currency code: {}", code)));
+        }
+        Ok(Currency(code.to_ascii_uppercase()))
+    }
+
+    /// Returns the ISO 4217 code of the currency.
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns number of decimals used by the currency.
+    pub fn minor_units(&self) -> u32 {
+        match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    /// Returns symbol of the currency, or the code if it has no common symbol.
+    pub fn symbol(&self) -> &str {
+        match self.code() {
+            "EUR" => "€",
+            "USD" => "$",
+            "GBP" => "£",
+            "JPY" => "¥",
+            code => code,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Currency> {
+        Currency::new(code)
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Currency, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Currency::new(&code).map_err(de::Error::custom)
+    }
+}
+
+/// Exact decimal amount in a currency.
+///
+/// Amounts of the same currency can be added, subtracted and compared.
+/// Mixing currencies is a programming error: the operators panic and the
+/// checked variants return None. Display formats the amount in Finnish
+/// conventions, e.g. `-1 234,50 €`, rounding half away from zero to the
+/// decimals of the currency.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    #[serde(with = "amount")]
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// Creates new Money.
+    pub fn new(amount: Decimal, currency: Currency) -> Money {
+        Money { amount, currency }
+    }
+
+    /// Parses amount such as `-12.50` in the given currency.
+    pub fn parse(amount: &str, currency: &str) -> Result<Money> {
+        let amount = Decimal::from_str(amount.trim())
+            .map_err(|e| Error::Config(format!("invalid amount {}: {}", amount, e)))?;
+        Ok(Money::new(amount, Currency::new(currency)?))
+    }
+
+    /// Returns zero in the given currency.
+    pub fn zero(currency: Currency) -> Money {
+        Money::new(Decimal::ZERO, currency)
+    }
+
+    /// Returns the amount.
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// Returns the currency.
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// Returns true if the amount is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_sign_negative() && !self.amount.is_zero()
+    }
+
+    /// Returns true if the amount is zero.
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    /// Returns the absolute amount.
+    pub fn abs(&self) -> Money {
+        Money::new(self.amount.abs(), self.currency.clone())
+    }
+
+    /// Adds two amounts. Returns None if the currencies differ or the sum
+    /// overflows.
+    pub fn checked_add(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        let amount = self.amount.checked_add(other.amount)?;
+        Some(Money::new(amount, self.currency.clone()))
+    }
+
+    /// Subtracts other from the amount. Returns None if the currencies
+    /// differ or the difference overflows.
+    pub fn checked_sub(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        let amount = self.amount.checked_sub(other.amount)?;
+        Some(Money::new(amount, self.currency.clone()))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    /// # Panics
+    ///
+    /// Panics if the currencies differ.
+    fn add(self, other: Money) -> Money {
+        assert_eq!(self.currency, other.currency, "currency mismatch");
+        Money::new(self.amount + other.amount, self.currency)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    /// # Panics
+    ///
+    /// Panics if the currencies differ.
+    fn sub(self, other: Money) -> Money {
+        assert_eq!(self.currency, other.currency, "currency mismatch");
+        Money::new(self.amount - other.amount, self.currency)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::new(-self.amount, self.currency)
+    }
+}
+
+/// Amounts in different currencies are not comparable.
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+        self.amount.partial_cmp(&other.amount)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rounded = self.amount.round_dp_with_strategy(
+            self.currency.minor_units(),
+            RoundingStrategy::MidpointAwayFromZero,
+        );
+        let digits = format!("{:.*}", self.currency.minor_units() as usize, rounded.abs());
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+
+        let mut out = String::new();
+        if rounded.is_sign_negative() && !rounded.is_zero() {
+            out.push('-');
+        }
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                out.push(GROUP_SEPARATOR);
+            }
+            out.push(c);
+        }
+        if let Some(fraction) = fraction {
+            out.push(',');
+            out.push_str(fraction);
+        }
+        out.push(GROUP_SEPARATOR);
+        out.push_str(self.currency.symbol());
+        f.write_str(&out)
+    }
+}
+
+/// Serde functions for decimal amounts.
+///
+/// Amounts are serialized as strings to keep them exact. Both strings and
+/// JSON numbers are accepted when deserializing.
+pub(crate) mod amount {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        amount: &Decimal,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&amount.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Decimal, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+
+    struct AmountVisitor;
+
+    impl<'de> Visitor<'de> for AmountVisitor {
+        type Value = Decimal;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a decimal amount as string or number")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Decimal, E> {
+            Decimal::from_str(value.trim())
+                .or_else(|_| Decimal::from_scientific(value.trim()))
+                .map_err(|e| E::custom(format!("{}: {}", value, e)))
+        }
+
+        // serde_json passes numbers with all their digits as a map
+        fn visit_map<A: de::MapAccess<'de>>(
+            self,
+            map: A,
+        ) -> std::result::Result<Decimal, A::Error> {
+            let number =
+                serde_json::Number::deserialize(de::value::MapAccessDeserializer::new(map))?;
+            self.visit_str(&number.to_string())
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Decimal, E> {
+            Ok(Decimal::from(value))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Decimal, E> {
+            Ok(Decimal::from(value))
+        }
+
+        // Shortest representation of the float is what the API sent
+        fn visit_f64<E: de::Error>(self, value: f64) -> std::result::Result<Decimal, E> {
+            if !value.is_finite() {
+                return Err(E::custom(format!("invalid amount: {}", value)));
+            }
+            Decimal::from_str(&value.to_string())
+                .or_else(|_| Decimal::from_scientific(&format!("{:e}", value)))
+                .map_err(|e| E::custom(format!("{}: {}", value, e)))
+        }
+    }
+
+    /// Same as the parent module for optional amounts, but serialized as
+    /// exact JSON numbers like the API sends account balances.
+    pub mod option_number {
+        use super::*;
+        use serde::ser::Error as _;
+
+        pub fn serialize<S: Serializer>(
+            amount: &Option<Decimal>,
+            serializer: S,
+        ) -> std::result::Result<S::Ok, S::Error> {
+            match amount {
+                Some(amount) => serde_json::Number::from_str(&amount.to_string())
+                    .map_err(S::Error::custom)?
+                    .serialize(serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Option<Decimal>, D::Error> {
+            #[derive(Deserialize)]
+            struct Wrapper(#[serde(with = "super")] Decimal);
+            let amount = Option::<Wrapper>::deserialize(deserializer)?;
+            Ok(amount.map(|Wrapper(amount)| amount))
+        }
+    }
+}
@@ -1,10 +1,121 @@
+use crate::auth::Authenticator;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::interceptor::Interceptor;
+use crate::rate_limit::RateLimiter;
+use crate::redact::Redacted;
+use crate::retry::RetryPolicy;
+use crate::tls::TlsConfig;
+use crate::transport::HttpTransport;
+use reqwest::header::HeaderValue;
+use reqwest::{Client, Proxy, Url};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Authorization used for the sandbox environment.
+///
+/// This is one of the predefined authorization keys in https://op-developer.fi/docs
+const SANDBOX_AUTHORIZATION: &str = "b6910384440ce06f495976f96a162e2ab1bafbb4";
+
+/// Default user agent sent with the requests.
+const USER_AGENT: &str = concat!("op-api-sdk/", env!("CARGO_PKG_VERSION"));
+
+/// Environment the requests are sent to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Environment {
+    /// Production environment at https://prod.apis.op-palvelut.fi
+    Production,
+    /// Sandbox environment at https://sandbox.apis.op-palvelut.fi
+    Sandbox,
+    /// Custom base URL, for example a proxy or a local mock server.
+    Custom(String),
+}
+
+impl Environment {
+    /// Returns base URL of the environment.
+    pub fn base_url(&self) -> &str {
+        match self {
+            Environment::Production => "https://prod.apis.op-palvelut.fi",
+            Environment::Sandbox => "https://sandbox.apis.op-palvelut.fi",
+            Environment::Custom(url) => url,
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = Error;
+
+    /// Parses "production" or "sandbox", case-insensitively.
+    fn from_str(s: &str) -> Result<Environment> {
+        match s.trim().to_lowercase().as_str() {
+            "production" | "prod" => Ok(Environment::Production),
+            "sandbox" | "dev" => Ok(Environment::Sandbox),
+            _ => Err(Error::Config(format!("invalid environment: {}", s))),
+        }
+    }
+}
+
 /// Options for requests to https://op-developer.fi
-#[derive(Default)]
+///
+/// Cloned options share the same HTTP client and thus the same
+/// connection pool. The API key and authorization are redacted from the
+/// Debug output and zeroized when dropped.
+#[derive(Clone)]
 pub struct Options {
-    api_key: String,
-    authorization: String,
-    version: String,
+    api_key: Zeroizing<String>,
+    authorization: Zeroizing<String>,
+    version: Option<String>,
+    api_versions: HashMap<String, String>,
     base_url: String,
+    client: Client,
+    client_settings: Option<ClientSettings>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    authenticator: Option<Authenticator>,
+    request_id: Option<String>,
+    session_id: Option<String>,
+}
+
+/// Settings the HTTP client was built with.
+///
+/// Kept so that the client can be rebuilt when the connect timeout changes.
+/// Not set for clients given with set_client.
+#[derive(Clone, Default)]
+struct ClientSettings {
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    tls: Option<Arc<TlsConfig>>,
+}
+
+impl ClientSettings {
+    /// Builds HTTP client from the settings.
+    fn build_client(&self, connect_timeout: Option<Duration>) -> Result<Client> {
+        let user_agent = self.user_agent.as_deref().unwrap_or(USER_AGENT);
+        let mut builder = Client::builder().user_agent(user_agent);
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy.as_str())
+                .map_err(|e| Error::Config(format!("invalid proxy: {}", e)))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(timeout) = connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(tls) = &self.tls {
+            builder = tls.apply(builder)?;
+        }
+        builder
+            .build()
+            .map_err(|e| Error::Config(format!("failed to build HTTP client: {}", e)))
+    }
 }
 
 impl Options {
@@ -13,12 +124,7 @@ impl Options {
     /// Keep in mind that authorization must be feched from the
     /// OAuth and passed here without 'Bearer' included.
     pub fn new(api_key: String, authorization: String) -> Options {
-        Options {
-            api_key,
-            authorization,
-            version: String::from("v1"),
-            base_url: String::from("https://prod.apis.op-palvelut.fi/"),
-        }
+        Options::with_defaults(api_key, authorization, Environment::Production)
     }
 
     /// Creates new Options struct for sandbox access
@@ -26,14 +132,58 @@ impl Options {
     /// Authorization for this is not required as it uses one of the
     /// predefined authorization keys in https://op-developer.fi/docs
     pub fn new_dev(api_key: String) -> Options {
-        Options {
+        Options::with_defaults(
             api_key,
-            authorization: String::from("b6910384440ce06f495976f96a162e2ab1bafbb4"),
-            version: String::from("v1"),
-            base_url: String::from("https://sandbox.apis.op-palvelut.fi/"),
+            String::from(SANDBOX_AUTHORIZATION),
+            Environment::Sandbox,
+        )
+    }
+
+    /// Creates new OptionsBuilder for configuring and validating options.
+    pub fn builder() -> OptionsBuilder {
+        OptionsBuilder::new()
+    }
+
+    /// Creates Options from the environment variables.
+    ///
+    /// See config module for the variable names. Only X_API_KEY is required.
+    pub fn from_env() -> Result<Options> {
+        Config::from_env()?.build()
+    }
+
+    /// Creates Options from a TOML or JSON configuration file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Options> {
+        Config::from_file(path)?.build()
+    }
+
+    fn with_defaults(api_key: String, authorization: String, environment: Environment) -> Options {
+        Options {
+            api_key: Zeroizing::new(api_key),
+            authorization: Zeroizing::new(authorization),
+            version: None,
+            api_versions: HashMap::new(),
+            base_url: environment.base_url().to_string(),
+            client: Client::new(),
+            client_settings: Some(ClientSettings::default()),
+            transport: None,
+            interceptors: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            connect_timeout: None,
+            timeout: None,
+            authenticator: None,
+            request_id: None,
+            session_id: None,
         }
     }
 
+    /// Sets base URL for requests.
+    ///
+    /// Can be used to direct requests to a proxy or a local mock server.
+    pub fn set_base_url(&mut self, base_url: String) {
+        self.base_url = base_url;
+    }
+
     /// Gets base URL for requests.
     ///
     /// This is different for production and sandbox environments.
@@ -45,26 +195,500 @@ impl Options {
     ///
     /// This is used as HTTP header x-api-key.
     pub fn api_key(&self) -> &str {
-        &self.api_key
+        self.api_key.as_str()
     }
 
     /// Gets Authorization for requests.
     ///
     /// This is used together with 'Bearer ' in the Authorization HTTP header.
+    /// Not used if an Authenticator is set.
     pub fn authorization(&self) -> &str {
-        &self.authorization
+        self.authorization.as_str()
     }
 
-    /// Sets API version for requests.
+    /// Sets Authenticator for obtaining and refreshing access tokens.
     ///
-    /// Some APIs are on different version (AccountsV3 for example).
+    /// Tokens from the Authenticator are used instead of the static
+    /// authorization.
+    pub fn set_authenticator(&mut self, authenticator: Authenticator) {
+        self.authenticator = Some(authenticator);
+    }
+
+    /// Returns Authenticator for requests, if any.
+    pub fn authenticator(&self) -> Option<&Authenticator> {
+        self.authenticator.as_ref()
+    }
+
+    /// Sets API version for a single API, for example "accounts".
+    ///
+    /// Clients use the version they implement by default, so this is only
+    /// needed to target another version of the API.
     /// This is information is used to construct the request URL.
+    pub fn set_api_version(&mut self, api: &str, version: String) {
+        self.api_versions.insert(api.to_string(), version);
+    }
+
+    /// Returns API version override for a single API, if any.
+    ///
+    /// Falls back to the version set with the deprecated set_version.
+    pub fn api_version(&self, api: &str) -> Option<&str> {
+        self.api_versions
+            .get(api)
+            .or(self.version.as_ref())
+            .map(|v| v.as_str())
+    }
+
+    /// Sets API version for all APIs without a version set with
+    /// set_api_version.
+    #[deprecated(
+        note = "clients use the version they implement, use set_api_version to override it"
+    )]
     pub fn set_version(&mut self, version: String) {
-        self.version = version;
+        self.version = Some(version);
     }
 
-    /// Returns API version for requests.
+    /// Returns API version set with set_version, or empty string if it is
+    /// not set.
+    #[deprecated(note = "use api_version or the version of the client")]
     pub fn version(&self) -> &str {
-        &self.version
+        self.version.as_deref().unwrap_or("")
+    }
+
+    /// Sets HTTP client used for requests.
+    ///
+    /// Can be used to pass a pre-configured reqwest client. The client
+    /// keeps a connection pool so it should be reused between requests.
+    /// This replaces a transport set earlier with set_transport. Connect
+    /// timeout of the client must be configured on the client itself.
+    pub fn set_client(&mut self, client: Client) {
+        self.client = client;
+        self.client_settings = None;
+        self.connect_timeout = None;
+        self.transport = None;
+    }
+
+    /// Returns HTTP client used for requests.
+    ///
+    /// Not used if a custom transport is set.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Sets custom transport used for all requests, including the token
+    /// requests of the Authenticator.
+    ///
+    /// The HTTP client and its settings, such as connect timeout, are not
+    /// used with a custom transport.
+    pub fn set_transport(&mut self, transport: Arc<dyn HttpTransport>) {
+        self.transport = Some(transport);
+    }
+
+    /// Returns transport used for requests.
+    ///
+    /// This is the HTTP client unless a custom transport is set.
+    pub fn transport(&self) -> &dyn HttpTransport {
+        match &self.transport {
+            Some(transport) => transport.as_ref(),
+            None => &self.client,
+        }
+    }
+
+    /// Adds interceptor to the request pipeline.
+    ///
+    /// Interceptors are called in the order they were added before the
+    /// requests are sent and in reverse order after the responses.
+    pub fn add_interceptor(&mut self, interceptor: Arc<dyn Interceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    /// Returns interceptors of the request pipeline.
+    pub fn interceptors(&self) -> &[Arc<dyn Interceptor>] {
+        &self.interceptors
+    }
+
+    /// Sets policy for retrying failed requests.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Returns policy for retrying failed requests.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Sets rate limiter for requests.
+    ///
+    /// The limiter is shared by all clones of these options so all
+    /// clients created from them count towards the same quota.
+    pub fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = Some(rate_limiter);
+    }
+
+    /// Returns rate limiter for requests, if any.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Sets timeout for establishing connections.
+    ///
+    /// This rebuilds the HTTP client with the user agent, proxy and TLS
+    /// configuration it was built with. Fails for clients set with
+    /// set_client and custom transports, whose connections are not managed
+    /// by these options.
+    pub fn set_connect_timeout(&mut self, timeout: Duration) -> Result<()> {
+        if timeout == Duration::from_secs(0) {
+            return Err(Error::Config(String::from("timeout must be positive")));
+        }
+        let settings = match &self.client_settings {
+            Some(settings) if self.transport.is_none() => settings,
+            _ => {
+                return Err(Error::Config(String::from(
+                    "connect timeout cannot be set with a custom client or transport",
+                )))
+            }
+        };
+        self.client = settings.build_client(Some(timeout))?;
+        self.connect_timeout = Some(timeout);
+        Ok(())
+    }
+
+    /// Returns timeout for establishing connections, if any.
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Sets timeout for the whole request, from connecting until the
+    /// response body has been read.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Returns timeout for the whole request, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Sets id sent in the x-request-id header.
+    ///
+    /// A new id is generated for each request unless one is set. Usually
+    /// set for single calls with the with_request_id of the clients.
+    pub fn set_request_id(&mut self, request_id: &str) {
+        self.request_id = Some(request_id.to_string());
+    }
+
+    /// Returns id sent in the x-request-id header, if set.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Sets id sent in the x-session-id header with every request.
+    ///
+    /// Can be used to correlate all requests of a user session.
+    pub fn set_session_id(&mut self, session_id: &str) {
+        self.session_id = Some(session_id.to_string());
+    }
+
+    /// Returns id sent in the x-session-id header, if set.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+}
+
+impl fmt::Debug for Options {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Options")
+            .field("api_key", &Redacted)
+            .field("authorization", &Redacted)
+            .field("version", &self.version)
+            .field("api_versions", &self.api_versions)
+            .field("base_url", &self.base_url)
+            .field("custom_transport", &self.transport.is_some())
+            .field("interceptors", &self.interceptors.len())
+            .field("retry_policy", &self.retry_policy)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("timeout", &self.timeout)
+            .field("authenticator", &self.authenticator)
+            .field("request_id", &self.request_id)
+            .field("session_id", &self.session_id)
+            .finish()
+    }
+}
+
+/// Builder for Options.
+///
+/// Validates the configuration when Options are built. Example:
+///
+/// ```
+/// use op_api_sdk::options::{Environment, Options};
+/// use std::time::Duration;
+///
+/// let options = Options::builder()
+///     .api_key("X_API_KEY")
+///     .environment(Environment::Sandbox)
+///     .timeout(Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// assert_eq!("https://sandbox.apis.op-palvelut.fi", options.base_url());
+/// ```
+pub struct OptionsBuilder {
+    api_key: Option<Zeroizing<String>>,
+    authorization: Option<Zeroizing<String>>,
+    environment: Environment,
+    api_versions: HashMap<String, String>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    tls: Option<TlsConfig>,
+    client: Option<Client>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    authenticator: Option<Authenticator>,
+    session_id: Option<String>,
+}
+
+impl Default for OptionsBuilder {
+    fn default() -> OptionsBuilder {
+        OptionsBuilder {
+            api_key: None,
+            authorization: None,
+            environment: Environment::Sandbox,
+            api_versions: HashMap::new(),
+            user_agent: None,
+            proxy: None,
+            tls: None,
+            client: None,
+            transport: None,
+            interceptors: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            connect_timeout: None,
+            timeout: None,
+            authenticator: None,
+            session_id: None,
+        }
+    }
+}
+
+impl OptionsBuilder {
+    /// Creates new OptionsBuilder for the sandbox environment.
+    pub fn new() -> OptionsBuilder {
+        OptionsBuilder::default()
+    }
+
+    /// Sets API key used in the x-api-key header. Required.
+    pub fn api_key(mut self, api_key: &str) -> OptionsBuilder {
+        self.api_key = Some(Zeroizing::new(api_key.to_string()));
+        self
+    }
+
+    /// Sets access token used in the Authorization header without 'Bearer'.
+    ///
+    /// Required for production. Defaults to the predefined sandbox
+    /// authorization for sandbox.
+    pub fn authorization(mut self, authorization: &str) -> OptionsBuilder {
+        self.authorization = Some(Zeroizing::new(authorization.to_string()));
+        self
+    }
+
+    /// Sets Authenticator for obtaining and refreshing access tokens.
+    pub fn authenticator(mut self, authenticator: Authenticator) -> OptionsBuilder {
+        self.authenticator = Some(authenticator);
+        self
+    }
+
+    /// Sets environment the requests are sent to. Defaults to sandbox.
+    pub fn environment(mut self, environment: Environment) -> OptionsBuilder {
+        self.environment = environment;
+        self
+    }
+
+    /// Sets custom base URL. Shorthand for Environment::Custom.
+    pub fn base_url(self, base_url: &str) -> OptionsBuilder {
+        self.environment(Environment::Custom(base_url.to_string()))
+    }
+
+    /// Sets API version for a single API, for example "accounts".
+    ///
+    /// Clients use the version they implement by default.
+    pub fn api_version(mut self, api: &str, version: &str) -> OptionsBuilder {
+        self.api_versions
+            .insert(api.to_string(), version.to_string());
+        self
+    }
+
+    /// Sets User-Agent header. Defaults to op-api-sdk/<version>.
+    pub fn user_agent(mut self, user_agent: &str) -> OptionsBuilder {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sets proxy URL used for all requests.
+    pub fn proxy(mut self, proxy: &str) -> OptionsBuilder {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Sets TLS configuration with client certificate and trusted roots.
+    ///
+    /// Applied to every request made with the Options, including token
+    /// requests.
+    pub fn tls(mut self, tls: TlsConfig) -> OptionsBuilder {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sets pre-configured HTTP client.
+    ///
+    /// Cannot be combined with user agent, proxy, TLS or connect timeout as
+    /// those are properties of the client.
+    pub fn client(mut self, client: Client) -> OptionsBuilder {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets custom transport used instead of the HTTP client.
+    ///
+    /// Cannot be combined with a client, user agent, proxy, TLS or connect
+    /// timeout as those are properties of the HTTP client.
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> OptionsBuilder {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Adds interceptor to the request pipeline.
+    pub fn interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> OptionsBuilder {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Sets policy for retrying failed requests.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> OptionsBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets rate limiter for requests.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> OptionsBuilder {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Sets timeout for establishing connections.
+    pub fn connect_timeout(mut self, timeout: Duration) -> OptionsBuilder {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets timeout for the whole request.
+    pub fn timeout(mut self, timeout: Duration) -> OptionsBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets id sent in the x-session-id header with every request.
+    pub fn session_id(mut self, session_id: &str) -> OptionsBuilder {
+        self.session_id = Some(session_id.to_string());
+        self
+    }
+
+    /// Validates the configuration and builds Options.
+    pub fn build(self) -> Result<Options> {
+        let api_key = match self.api_key {
+            Some(key) if !key.trim().is_empty() => key,
+            _ => return Err(Error::Config(String::from("API key is not set"))),
+        };
+        let authorization = match (self.authorization, &self.environment) {
+            (Some(auth), _) => auth,
+            (None, Environment::Sandbox) => Zeroizing::new(String::from(SANDBOX_AUTHORIZATION)),
+            (None, _) if self.authenticator.is_some() => Zeroizing::new(String::new()),
+            (None, Environment::Production) => {
+                return Err(Error::Config(String::from(
+                    "authorization or authenticator is required for production",
+                )))
+            }
+            (None, Environment::Custom(_)) => Zeroizing::new(String::new()),
+        };
+        let base_url = normalize_base_url(self.environment.base_url())?;
+        if let Some(session_id) = &self.session_id {
+            HeaderValue::from_str(session_id).map_err(|_| {
+                Error::Config(String::from("session id contains invalid characters"))
+            })?;
+        }
+        for timeout in [self.connect_timeout, self.timeout].iter().flatten() {
+            if *timeout == Duration::from_secs(0) {
+                return Err(Error::Config(String::from("timeout must be positive")));
+            }
+        }
+
+        let client_settings = self.user_agent.is_some()
+            || self.proxy.is_some()
+            || self.tls.is_some()
+            || self.connect_timeout.is_some();
+        if self.transport.is_some() && (self.client.is_some() || client_settings) {
+            return Err(Error::Config(String::from(
+                "client, user agent, proxy, TLS and connect timeout cannot be set with a custom transport",
+            )));
+        }
+        let (client, settings) = match self.client {
+            Some(client) => {
+                if client_settings {
+                    return Err(Error::Config(String::from(
+                        "user agent, proxy, TLS and connect timeout cannot be set with a custom client",
+                    )));
+                }
+                (client, None)
+            }
+            None if self.transport.is_some() => (Client::new(), None),
+            None => {
+                let settings = ClientSettings {
+                    user_agent: self.user_agent,
+                    proxy: self.proxy,
+                    tls: self.tls.map(Arc::new),
+                };
+                (settings.build_client(self.connect_timeout)?, Some(settings))
+            }
+        };
+
+        Ok(Options {
+            api_key,
+            authorization,
+            version: None,
+            api_versions: self.api_versions,
+            base_url,
+            client,
+            client_settings: settings,
+            transport: self.transport,
+            interceptors: self.interceptors,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            connect_timeout: self.connect_timeout,
+            timeout: self.timeout,
+            authenticator: self.authenticator,
+            request_id: None,
+            session_id: self.session_id,
+        })
+    }
+}
+
+/// Validates base URL and removes trailing slashes from it.
+fn normalize_base_url(base_url: &str) -> Result<String> {
+    let url = Url::parse(base_url)
+        .map_err(|e| Error::Config(format!("invalid base URL {}: {}", base_url, e)))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(Error::Config(format!(
+            "base URL must use http or https: {}",
+            base_url
+        )));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(Error::Config(format!(
+            "base URL must not contain query or fragment: {}",
+            base_url
+        )));
     }
+    Ok(base_url.trim_end_matches('/').to_string())
 }
@@ -0,0 +1,85 @@
+use crate::transport::HttpTransport;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+
+/// Token bucket state shared between clones of RateLimiter.
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    per_second: f64,
+    updated: Instant,
+}
+
+/// Client-side token bucket rate limiter.
+///
+/// Allows bursts up to the given number of requests and refills evenly
+/// over the given period. Clones share the same bucket, so all clients
+/// created from the same Options are limited together. Requests exceeding
+/// the quota wait for their turn in order instead of failing.
+#[derive(Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates new RateLimiter allowing given number of requests per period.
+    pub fn new(requests: u32, per: Duration) -> RateLimiter {
+        let capacity = f64::from(requests.max(1));
+        let seconds = per.as_secs_f64().max(f64::EPSILON);
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(Bucket {
+                capacity,
+                tokens: capacity,
+                per_second: capacity / seconds,
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Reserves a token and returns how long the caller must wait for it.
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * bucket.per_second).min(bucket.capacity);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.per_second)
+        }
+    }
+
+    /// Waits until a request is allowed to be sent.
+    ///
+    /// Waits with the tokio timer. Clients wait with the sleep of their
+    /// transport instead.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if wait > Duration::from_secs(0) {
+            delay_for(wait).await;
+        }
+    }
+
+    /// Waits until a request is allowed to be sent, sleeping with the
+    /// transport.
+    pub(crate) async fn acquire_with(&self, transport: &dyn HttpTransport) {
+        let wait = self.reserve();
+        if wait > Duration::from_secs(0) {
+            transport.sleep(wait).await;
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bucket = self.bucket.lock().unwrap();
+        f.debug_struct("RateLimiter")
+            .field("capacity", &bucket.capacity)
+            .field("per_second", &bucket.per_second)
+            .finish()
+    }
+}
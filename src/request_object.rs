@@ -0,0 +1,157 @@
+use crate::error::{Error, Result};
+use crate::redact::Redacted;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Request objects are valid for this many seconds after they are issued.
+const LIFETIME_SECS: i64 = 300;
+
+/// Algorithm used to sign request objects.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SigningAlgorithm {
+    /// RSASSA-PKCS1-v1_5 with SHA-256.
+    RS256,
+    /// RSASSA-PSS with SHA-256.
+    PS256,
+}
+
+impl SigningAlgorithm {
+    fn algorithm(self) -> Algorithm {
+        match self {
+            SigningAlgorithm::RS256 => Algorithm::RS256,
+            SigningAlgorithm::PS256 => Algorithm::PS256,
+        }
+    }
+}
+
+/// Claims of a PSD2 authorization request object.
+///
+/// Serialized with the OAuth2 and OpenID Connect parameter names.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RequestObject {
+    /// Issuer, i.e. the client id of the TPP.
+    pub iss: String,
+    /// Audience, i.e. the issuer of the authorization server.
+    pub aud: String,
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_challenge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_challenge_method: Option<String>,
+    /// Issued at as seconds since the epoch.
+    pub iat: i64,
+    /// Expiration time as seconds since the epoch.
+    pub exp: i64,
+    /// Unique identifier of the request object.
+    pub jti: String,
+    /// Requested claims, including the authorizationId of the consent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claims: Option<Value>,
+}
+
+impl RequestObject {
+    /// Creates new RequestObject issued now for the authorization code flow.
+    pub fn new(
+        client_id: &str,
+        audience: &str,
+        redirect_uri: &str,
+        state: &str,
+        jti: &str,
+    ) -> RequestObject {
+        let iat = Utc::now().timestamp();
+        RequestObject {
+            iss: client_id.to_string(),
+            aud: audience.to_string(),
+            response_type: String::from("code"),
+            client_id: client_id.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            scope: None,
+            state: state.to_string(),
+            nonce: None,
+            code_challenge: None,
+            code_challenge_method: None,
+            iat,
+            exp: iat + Duration::seconds(LIFETIME_SECS).num_seconds(),
+            jti: jti.to_string(),
+            claims: None,
+        }
+    }
+
+    /// Requests the given authorizationId, i.e. the consent being authorized,
+    /// as an essential claim in both userinfo and ID token.
+    pub fn set_authorization_id(&mut self, authorization_id: &str) {
+        let claim = json!({ "value": authorization_id, "essential": true });
+        self.claims = Some(json!({
+            "userinfo": { "authorizationId": claim },
+            "id_token": { "authorizationId": claim },
+        }));
+    }
+}
+
+/// Signs request objects with the TPP's RSA key.
+#[derive(Clone)]
+pub struct RequestObjectSigner {
+    key: EncodingKey,
+    algorithm: SigningAlgorithm,
+    key_id: Option<String>,
+    audience: String,
+}
+
+impl RequestObjectSigner {
+    /// Creates new RequestObjectSigner from PEM encoded RSA private key.
+    ///
+    /// Audience is the issuer identifier of the authorization server.
+    pub fn from_rsa_pem(
+        pem: &[u8],
+        algorithm: SigningAlgorithm,
+        audience: &str,
+    ) -> Result<RequestObjectSigner> {
+        let key = EncodingKey::from_rsa_pem(pem)
+            .map_err(|e| Error::Config(format!("invalid signing key: {}", e)))?;
+        Ok(RequestObjectSigner {
+            key,
+            algorithm,
+            key_id: None,
+            audience: audience.to_string(),
+        })
+    }
+
+    /// Sets id of the signing key registered for the TPP, sent as kid header.
+    pub fn set_key_id(&mut self, key_id: &str) {
+        self.key_id = Some(key_id.to_string());
+    }
+
+    /// Returns audience of the request objects.
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+
+    /// Signs the request object and returns it as compact JWT.
+    pub fn sign(&self, request: &RequestObject) -> Result<String> {
+        let mut header = Header::new(self.algorithm.algorithm());
+        header.kid = self.key_id.clone();
+        encode(&header, request, &self.key)
+            .map_err(|e| Error::Config(format!("failed to sign request object: {}", e)))
+    }
+}
+
+impl fmt::Debug for RequestObjectSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestObjectSigner")
+            .field("key", &Redacted)
+            .field("algorithm", &self.algorithm)
+            .field("key_id", &self.key_id)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
@@ -1,69 +1,225 @@
+use crate::error::{ApiErrors, Error, Result, REQUEST_ID_HEADER};
 use crate::options::Options;
-use log::debug;
-use reqwest::{Client, RequestBuilder, Response, StatusCode};
-use serde::{Deserialize, Serialize};
-use std::error::Error;
-use std::fmt;
+use crate::redact::Headers;
+use crate::response::ApiResponse;
+use crate::transport::{HttpRequest, HttpResponse};
+use log::{debug, warn};
+use rand::Rng;
+use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
+/// Header carrying the id of the user session.
+const SESSION_ID_HEADER: &str = "x-session-id";
+
+/// Request functionality shared by all clients.
 pub struct Requests;
 
-/// Single error from OP API.
-#[derive(Deserialize, Debug, Clone)]
-pub struct ApiError {
-    pub id: String,
-    pub level: String,
-    pub r#type: String,
-    pub message: String,
+/// Checks that options contain everything needed for a request.
+fn check_options(options: &Options) -> Result<()> {
+    if options.base_url().is_empty() {
+        return Err(Error::Config(String::from("base URL is not set")));
+    }
+    if options.api_key().is_empty() {
+        return Err(Error::Config(String::from("API key is not set")));
+    }
+    Ok(())
+}
+
+/// Constructs URL from base url and API url.
+///
+/// Exactly one slash is used between the base url and the API url.
+fn get_request_url(options: &Options, url: &str) -> Result<Url> {
+    let url = format!(
+        "{base_url}/{url}",
+        base_url = options.base_url().trim_end_matches('/'),
+        url = url.trim_start_matches('/')
+    );
+    Url::parse(&url).map_err(|e| Error::Config(format!("invalid request URL {}: {}", url, e)))
 }
 
-/// Container for API errors from OP API.
-#[derive(Deserialize, Debug, Clone)]
-pub struct ApiErrors {
-    pub errors: Vec<ApiError>,
+/// Sets necessary headers for the request.
+fn set_headers(options: &Options, authorization: &str, request: &mut HttpRequest) -> Result<()> {
+    let invalid = |name: &str| Error::Config(format!("{} contains invalid characters", name));
+    let api_key = HeaderValue::from_str(options.api_key()).map_err(|_| invalid("API key"))?;
+    let mut bearer = HeaderValue::from_str(&format!("{} {}", "Bearer", authorization))
+        .map_err(|_| invalid("authorization"))?;
+    bearer.set_sensitive(true);
+    request.headers.insert("x-api-key", api_key);
+    request.headers.insert(AUTHORIZATION, bearer);
+    request
+        .headers
+        .insert(ACCEPT, HeaderValue::from_static("application/json"));
+    Ok(())
 }
 
-/// Implement functionality to display ApiErrors.
-impl fmt::Display for ApiErrors {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "API error occurred, reasons: {:?}", self.errors)
+/// Sets the correlation headers for the request.
+fn set_id_headers(options: &Options, request_id: &str, request: &mut HttpRequest) -> Result<()> {
+    let invalid = |name: &str| Error::Config(format!("{} contains invalid characters", name));
+    let value = HeaderValue::from_str(request_id).map_err(|_| invalid("request id"))?;
+    request.headers.insert(REQUEST_ID_HEADER, value);
+    if let Some(session_id) = options.session_id() {
+        let value = HeaderValue::from_str(session_id).map_err(|_| invalid("session id"))?;
+        request.headers.insert(SESSION_ID_HEADER, value);
     }
+    Ok(())
 }
 
-/// Implement std::error::Error for ApiErrors.
-impl Error for ApiErrors {}
+/// Generates random UUID v4 used as request id.
+fn generate_request_id() -> String {
+    let mut bytes: [u8; 16] = rand::thread_rng().gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
 
-/// Constructs string URL from base url and API url.
-fn get_request_url(options: &Options, url: &str) -> String {
-    format!("{base_url}{url}", base_url = options.base_url(), url = url)
+/// Sets query parameters for the request.
+fn set_query_params<T: Serialize>(query: Option<T>, request: &mut HttpRequest) -> Result<()> {
+    if let Some(q) = query {
+        let mut pairs = request.url.query_pairs_mut();
+        q.serialize(serde_urlencoded::Serializer::new(&mut pairs))
+            .map_err(|e| Error::Config(format!("invalid query parameters: {}", e)))?;
+    }
+    // Serializer leaves an empty query if there were no parameters
+    if request.url.query() == Some("") {
+        request.url.set_query(None);
+    }
+    Ok(())
 }
 
-/// Sets necessary headers for the request.
-fn set_headers(options: &Options, builder: RequestBuilder) -> RequestBuilder {
-    builder
-        .header("x-api-key", options.api_key())
-        .header(
-            "Authorization",
-            format!("{} {}", "Bearer", options.authorization()),
-        )
-        .header("Accept", "application/json")
+/// Sets JSON body for the request.
+///
+/// This also sets the Content-Type header to application/json.
+fn set_body<B: Serialize>(body: Option<&B>, request: &mut HttpRequest) -> Result<()> {
+    if let Some(b) = body {
+        let json = serde_json::to_vec(b)
+            .map_err(|e| Error::Config(format!("invalid request body: {}", e)))?;
+        request
+            .headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        request.body = Some(json);
+    }
+    Ok(())
 }
 
-/// Sets query parameters for the request.
-fn set_query_params<T: Serialize>(query: Option<T>, builder: RequestBuilder) -> RequestBuilder {
-    match query {
-        Some(q) => builder.query(&q),
-        None => builder,
+/// Checks for possible API errors from the response.
+///
+/// Any 2xx status is considered successful. For other statuses the
+/// status, headers and raw body are kept even if the body is not
+/// a valid ApiErrors document.
+fn check_errors(response: HttpResponse) -> Result<HttpResponse> {
+    if response.status.is_success() {
+        return Ok(response);
     }
+    let body = response.text();
+    let errors = serde_json::from_str::<ApiErrors>(&body).ok();
+    Err(Error::Api {
+        status: response.status,
+        headers: Box::new(response.headers),
+        body,
+        errors,
+    })
 }
 
-/// Checks for possible API errors from the response
-async fn check_errors(response: Response) -> Result<Response, Box<dyn Error>> {
-    match response.status() {
-        StatusCode::OK => Ok(response),
-        _ => {
-            let errors: ApiErrors = response.json().await?;
-            Err(Box::new(errors))
+/// Sends the request through the transport and checks the response for
+/// errors.
+///
+/// Waits for the rate limiter first if one is configured and runs the
+/// request and the response through the interceptors. Credentials are
+/// masked in the logged headers.
+async fn execute(options: &Options, mut request: HttpRequest) -> Result<HttpResponse> {
+    if let Some(limiter) = options.rate_limiter() {
+        limiter.acquire_with(options.transport()).await;
+    }
+    for interceptor in options.interceptors() {
+        interceptor.before_request(&mut request)?;
+    }
+    debug!(
+        "Sending request: {} {} {:?}",
+        request.method,
+        request.url,
+        Headers(&request.headers)
+    );
+    if options.interceptors().is_empty() {
+        let response = options.transport().send(request).await?;
+        return check_errors(response);
+    }
+
+    // Transport takes the request, interceptors still need it afterwards
+    let result = options.transport().send(request.clone()).await;
+    let mut response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            for interceptor in options.interceptors().iter().rev() {
+                interceptor.on_error(&request, &e);
+            }
+            return Err(e);
+        }
+    };
+    for interceptor in options.interceptors().iter().rev() {
+        interceptor.after_response(&request, &mut response)?;
+    }
+    check_errors(response)
+}
+
+/// Sends the request, retrying it according to the retry policy.
+async fn send_with_retries(options: &Options, request: HttpRequest) -> Result<HttpResponse> {
+    let policy = options.retry_policy();
+    let retryable = policy.allows_method(&request.method);
+    let mut attempt = 1;
+    loop {
+        match execute(options, request.clone()).await {
+            Err(e) if retryable && attempt < policy.max_attempts() && policy.is_retryable(&e) => {
+                let delay = policy.delay(attempt, &e);
+                warn!(
+                    "Request attempt {} failed, retrying in {:?}: {}",
+                    attempt, delay, e
+                );
+                options.transport().sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Sets the authorization headers and sends the request.
+///
+/// Unauthorized requests are retried once with a refreshed token if an
+/// Authenticator is set.
+async fn send_authorized(options: &Options, mut request: HttpRequest) -> Result<HttpResponse> {
+    let authenticator = match options.authenticator() {
+        Some(authenticator) => authenticator,
+        None => {
+            set_headers(options, options.authorization(), &mut request)?;
+            return send_with_retries(options, request).await;
+        }
+    };
+
+    // Token can be revoked before it expires, so unauthorized requests
+    // are retried once with a refreshed token.
+    let access_token = authenticator.access_token(options.transport()).await?;
+    let mut retry = request.clone();
+    set_headers(options, &access_token, &mut request)?;
+    match send_with_retries(options, request).await {
+        Err(e) if e.status() == Some(StatusCode::UNAUTHORIZED) => {
+            debug!("Access token was rejected, refreshing it");
+            let access_token = authenticator
+                .refresh(options.transport(), &access_token)
+                .await?;
+            set_headers(options, &access_token, &mut retry)?;
+            send_with_retries(options, retry).await
         }
+        result => result,
     }
 }
 
@@ -77,12 +233,105 @@ impl Requests {
         options: &Options,
         url: &str,
         query: Option<T>,
-    ) -> Result<Response, Box<dyn Error>> {
-        let request_url = get_request_url(options, url);
-        let builder = Client::new().get(&request_url);
-        let client = set_headers(options, set_query_params(query, builder));
-        debug!("Sending request: {:?}", client);
-        let response = client.send().await?;
-        Ok(check_errors(response).await?)
+    ) -> Result<HttpResponse> {
+        Requests::send(options, Method::GET, url, query, None::<&()>).await
     }
+
+    /// Performs POST request with JSON body to API specified with url.
+    pub async fn post<T: Serialize, B: Serialize>(
+        options: &Options,
+        url: &str,
+        query: Option<T>,
+        body: &B,
+    ) -> Result<HttpResponse> {
+        Requests::send(options, Method::POST, url, query, Some(body)).await
+    }
+
+    /// Performs PUT request with JSON body to API specified with url.
+    pub async fn put<T: Serialize, B: Serialize>(
+        options: &Options,
+        url: &str,
+        query: Option<T>,
+        body: &B,
+    ) -> Result<HttpResponse> {
+        Requests::send(options, Method::PUT, url, query, Some(body)).await
+    }
+
+    /// Performs PATCH request with JSON body to API specified with url.
+    pub async fn patch<T: Serialize, B: Serialize>(
+        options: &Options,
+        url: &str,
+        query: Option<T>,
+        body: &B,
+    ) -> Result<HttpResponse> {
+        Requests::send(options, Method::PATCH, url, query, Some(body)).await
+    }
+
+    /// Performs DELETE request to API specified with url.
+    pub async fn delete<T: Serialize>(
+        options: &Options,
+        url: &str,
+        query: Option<T>,
+    ) -> Result<HttpResponse> {
+        Requests::send(options, Method::DELETE, url, query, None::<&()>).await
+    }
+
+    /// Performs request with given method to API specified with url.
+    ///
+    /// Request is sent with the x-request-id header. Response headers
+    /// always contain x-request-id, the sent one is added if the API did
+    /// not return one. The request id is also attached to the errors.
+    pub async fn send<T: Serialize, B: Serialize>(
+        options: &Options,
+        method: Method,
+        url: &str,
+        query: Option<T>,
+        body: Option<&B>,
+    ) -> Result<HttpResponse> {
+        check_options(options)?;
+        let request_id = match options.request_id() {
+            Some(request_id) => request_id.to_string(),
+            None => generate_request_id(),
+        };
+        let mut request = HttpRequest::new(method, get_request_url(options, url)?);
+        request.timeout = options.timeout();
+        set_query_params(query, &mut request)?;
+        set_body(body, &mut request)?;
+        set_id_headers(options, &request_id, &mut request)?;
+        match send_authorized(options, request).await {
+            Ok(mut response) => {
+                if !response.headers.contains_key(REQUEST_ID_HEADER) {
+                    let value = HeaderValue::from_str(&request_id)
+                        .expect("request id was validated already");
+                    response.headers.insert(REQUEST_ID_HEADER, value);
+                }
+                Ok(response)
+            }
+            Err(e) => Err(e.with_request_id(&request_id)),
+        }
+    }
+
+    /// Deserializes the response body from JSON.
+    ///
+    /// Raw body is kept in the error if deserialization fails.
+    pub fn json<T: DeserializeOwned>(response: HttpResponse) -> Result<T> {
+        serde_json::from_slice(&response.body).map_err(|source| Error::Deserialize {
+            source,
+            body: response.text(),
+            request_id: request_id(&response),
+        })
+    }
+
+    /// Deserializes the response body from JSON together with the request id.
+    pub fn api_response<T: DeserializeOwned>(response: HttpResponse) -> Result<ApiResponse<T>> {
+        let request_id = request_id(&response);
+        let data = Requests::json(response)?;
+        Ok(ApiResponse::new(data, request_id))
+    }
+}
+
+/// Returns the x-request-id header of the response.
+fn request_id(response: &HttpResponse) -> Option<String> {
+    let value = response.headers.get(REQUEST_ID_HEADER)?;
+    value.to_str().ok().map(String::from)
 }
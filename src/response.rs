@@ -0,0 +1,45 @@
+use std::ops::{Deref, DerefMut};
+
+/// Data returned by an API call together with the id of the request.
+///
+/// Dereferences to the data, so its fields can be used directly. OP support
+/// asks for the request id when reporting problems.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse<T> {
+    data: T,
+    request_id: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    /// Creates new ApiResponse.
+    pub fn new(data: T, request_id: Option<String>) -> ApiResponse<T> {
+        ApiResponse { data, request_id }
+    }
+
+    /// Returns id of the request.
+    ///
+    /// This is the x-request-id returned by the API or, if the API did not
+    /// return one, the id sent with the request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Returns the data, dropping the request id.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T> Deref for ApiResponse<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for ApiResponse<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// Policy for retrying failed requests.
+///
+/// By default requests are attempted 3 times with exponential backoff
+/// starting from 500ms. Only idempotent requests (GET, HEAD, OPTIONS, PUT and
+/// DELETE) are retried unless retrying non-idempotent requests is enabled.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    statuses: Vec<StatusCode>,
+    retry_transport_errors: bool,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_transport_errors: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates new RetryPolicy with default values.
+    pub fn new() -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Creates RetryPolicy that never retries.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Sets maximum number of attempts including the first one.
+    pub fn set_max_attempts(&mut self, max_attempts: u32) {
+        self.max_attempts = max_attempts.max(1);
+    }
+
+    /// Returns maximum number of attempts including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Sets backoff before the first retry. Backoff is doubled for each retry.
+    pub fn set_initial_backoff(&mut self, backoff: Duration) {
+        self.initial_backoff = backoff;
+    }
+
+    /// Sets upper limit for the backoff.
+    ///
+    /// Errors with a longer Retry-After are not retried.
+    pub fn set_max_backoff(&mut self, backoff: Duration) {
+        self.max_backoff = backoff;
+    }
+
+    /// Sets whether random jitter is applied to the backoff.
+    pub fn set_jitter(&mut self, jitter: bool) {
+        self.jitter = jitter;
+    }
+
+    /// Sets HTTP statuses that are retried.
+    pub fn set_statuses(&mut self, statuses: Vec<StatusCode>) {
+        self.statuses = statuses;
+    }
+
+    /// Sets whether connection errors and timeouts are retried.
+    pub fn set_retry_transport_errors(&mut self, retry: bool) {
+        self.retry_transport_errors = retry;
+    }
+
+    /// Sets whether non-idempotent requests (POST, PATCH) are retried.
+    pub fn set_retry_non_idempotent(&mut self, retry: bool) {
+        self.retry_non_idempotent = retry;
+    }
+
+    /// Returns true if requests with the method can be retried.
+    pub fn allows_method(&self, method: &Method) -> bool {
+        self.retry_non_idempotent
+            || matches!(
+                *method,
+                Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+            )
+    }
+
+    /// Returns true if the error is retryable.
+    ///
+    /// Errors asking to wait longer than the maximum backoff with
+    /// Retry-After are returned to the caller instead.
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        if let Some(retry_after) = error.retry_after() {
+            if retry_after > self.max_backoff {
+                return false;
+            }
+        }
+        match error {
+            Error::Api { status, .. } => self.statuses.contains(status),
+            Error::Transport(e) => self.retry_transport_errors && e.is_connect(),
+            Error::Timeout(_) => self.retry_transport_errors,
+            _ => false,
+        }
+    }
+
+    /// Returns delay before the given retry.
+    ///
+    /// Retry-After from the response is honored when present, up to the
+    /// maximum backoff.
+    pub fn delay(&self, retry: u32, error: &Error) -> Duration {
+        if let Some(retry_after) = error.retry_after() {
+            return retry_after.min(self.max_backoff);
+        }
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter && backoff > Duration::from_millis(0) {
+            let half = backoff / 2;
+            half + rand::thread_rng()
+                .gen_range(Duration::from_millis(0), half + Duration::from_nanos(1))
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Parses the value of Retry-After header.
+///
+/// Value can be either delay in seconds or HTTP date.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    let delay = date.with_timezone(&Utc) - Utc::now();
+    Some(delay.to_std().unwrap_or_else(|_| Duration::from_secs(0)))
+}
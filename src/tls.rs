@@ -0,0 +1,167 @@
+use crate::error::{Error, Result};
+use crate::redact::Redacted;
+use reqwest::{Certificate, ClientBuilder, Identity};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// Client certificate used for mutual TLS.
+///
+/// PKCS#12 archive, its password and the PEM private key are zeroized
+/// when dropped.
+#[derive(Clone)]
+enum ClientIdentity {
+    Pkcs12 {
+        der: Zeroizing<Vec<u8>>,
+        password: Zeroizing<String>,
+    },
+    Pem {
+        cert: Vec<u8>,
+        key: Zeroizing<Vec<u8>>,
+    },
+}
+
+/// TLS configuration for the HTTP client.
+///
+/// OP production PSD2 APIs require a QWAC client certificate. The identity
+/// can be given either as PKCS#12 or as PEM certificate chain and private
+/// key. PEM identities are supported on platforms where native-tls uses
+/// OpenSSL. The identity is redacted from the Debug output.
+#[derive(Clone, Default)]
+pub struct TlsConfig {
+    identity: Option<ClientIdentity>,
+    root_certificates: Vec<Certificate>,
+}
+
+impl TlsConfig {
+    /// Creates new empty TlsConfig.
+    pub fn new() -> TlsConfig {
+        TlsConfig::default()
+    }
+
+    /// Sets client identity from DER encoded PKCS#12 archive.
+    pub fn set_identity_pkcs12(&mut self, der: &[u8], password: &str) {
+        self.identity = Some(ClientIdentity::Pkcs12 {
+            der: Zeroizing::new(der.to_vec()),
+            password: Zeroizing::new(password.to_string()),
+        });
+    }
+
+    /// Sets client identity from PEM encoded certificate chain and private key.
+    pub fn set_identity_pem(&mut self, cert: &[u8], key: &[u8]) {
+        self.identity = Some(ClientIdentity::Pem {
+            cert: cert.to_vec(),
+            key: Zeroizing::new(key.to_vec()),
+        });
+    }
+
+    /// Adds trusted root certificate in PEM format.
+    pub fn add_root_certificate_pem(&mut self, pem: &[u8]) -> Result<()> {
+        let cert = Certificate::from_pem(pem)
+            .map_err(|e| Error::Config(format!("invalid root certificate: {}", e)))?;
+        self.root_certificates.push(cert);
+        Ok(())
+    }
+
+    /// Adds trusted root certificate in DER format.
+    pub fn add_root_certificate_der(&mut self, der: &[u8]) -> Result<()> {
+        let cert = Certificate::from_der(der)
+            .map_err(|e| Error::Config(format!("invalid root certificate: {}", e)))?;
+        self.root_certificates.push(cert);
+        Ok(())
+    }
+
+    /// Creates TlsConfig with identity read from PKCS#12 file.
+    pub fn from_pkcs12_file<P: AsRef<Path>>(path: P, password: &str) -> Result<TlsConfig> {
+        let mut tls = TlsConfig::new();
+        tls.set_identity_pkcs12(&read(path.as_ref())?, password);
+        Ok(tls)
+    }
+
+    /// Creates TlsConfig with identity read from PEM certificate and key files.
+    pub fn from_pem_files<P: AsRef<Path>>(cert: P, key: P) -> Result<TlsConfig> {
+        let mut tls = TlsConfig::new();
+        tls.set_identity_pem(&read(cert.as_ref())?, &read(key.as_ref())?);
+        Ok(tls)
+    }
+
+    /// Adds trusted root certificate read from PEM file.
+    pub fn add_root_certificate_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.add_root_certificate_pem(&read(path.as_ref())?)
+    }
+
+    /// Applies the configuration to the HTTP client builder.
+    pub(crate) fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        for cert in self.root_certificates.iter() {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        let identity = match &self.identity {
+            Some(ClientIdentity::Pkcs12 { der, password }) => {
+                Identity::from_pkcs12_der(der, password)
+                    .map_err(|e| Error::Config(format!("invalid PKCS#12 identity: {}", e)))?
+            }
+            Some(ClientIdentity::Pem { cert, key }) => pem_identity(cert, key)?,
+            None => return Ok(builder),
+        };
+        Ok(builder.identity(identity))
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let identity = match &self.identity {
+            Some(ClientIdentity::Pkcs12 { .. }) => Some("PKCS#12"),
+            Some(ClientIdentity::Pem { .. }) => Some("PEM"),
+            None => None,
+        };
+        f.debug_struct("TlsConfig")
+            .field("identity", &identity.map(|format| (format, Redacted)))
+            .field("root_certificates", &self.root_certificates.len())
+            .finish()
+    }
+}
+
+/// Converts PEM certificate chain and key to PKCS#12 understood by native-tls.
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+fn pem_identity(cert: &[u8], key: &[u8]) -> Result<Identity> {
+    use openssl::pkcs12::Pkcs12;
+    use openssl::pkey::PKey;
+    use openssl::stack::Stack;
+    use openssl::x509::X509;
+
+    let invalid =
+        |e: openssl::error::ErrorStack| Error::Config(format!("invalid PEM identity: {}", e));
+    let mut certs = X509::stack_from_pem(cert).map_err(invalid)?.into_iter();
+    let leaf = certs
+        .next()
+        .ok_or_else(|| Error::Config(String::from("PEM identity has no certificate")))?;
+    let key = PKey::private_key_from_pem(key).map_err(invalid)?;
+    let mut chain = Stack::new().map_err(invalid)?;
+    for cert in certs {
+        chain.push(cert).map_err(invalid)?;
+    }
+    // Password is only used between here and native-tls
+    let password = "op-api-sdk";
+    let der = Pkcs12::builder()
+        .name("client")
+        .pkey(&key)
+        .cert(&leaf)
+        .ca(chain)
+        .build2(password)
+        .and_then(|p| p.to_der())
+        .map_err(invalid)?;
+    Identity::from_pkcs12_der(&der, password)
+        .map_err(|e| Error::Config(format!("invalid PEM identity: {}", e)))
+}
+
+#[cfg(any(target_os = "windows", target_vendor = "apple"))]
+fn pem_identity(_cert: &[u8], _key: &[u8]) -> Result<Identity> {
+    Err(Error::Config(String::from(
+        "PEM identities are not supported on this platform, use PKCS#12",
+    )))
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|e| Error::Config(format!("failed to read {}: {}", path.display(), e)))
+}
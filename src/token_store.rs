@@ -0,0 +1,214 @@
+use crate::auth::Token;
+use crate::error::{Error, Result};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+#[cfg(feature = "encryption")]
+use zeroize::Zeroizing;
+
+/// Storage for access and refresh tokens.
+///
+/// Tokens are keyed by user or consent id. Implementations must be safe to
+/// share between threads. Methods are synchronous as tokens are small and
+/// only accessed when they are refreshed.
+pub trait TokenStore: Send + Sync {
+    /// Loads token for the key, if any.
+    fn load(&self, key: &str) -> Result<Option<Token>>;
+
+    /// Saves token for the key, replacing the old one.
+    fn save(&self, key: &str, token: &Token) -> Result<()>;
+
+    /// Removes token for the key.
+    fn remove(&self, key: &str) -> Result<()>;
+}
+
+/// TokenStore keeping the tokens in memory.
+///
+/// Tokens are lost when the process exits.
+#[derive(Default)]
+pub struct MemoryTokenStore {
+    tokens: Mutex<HashMap<String, Token>>,
+}
+
+impl MemoryTokenStore {
+    /// Creates new empty MemoryTokenStore.
+    pub fn new() -> MemoryTokenStore {
+        MemoryTokenStore::default()
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self, key: &str) -> Result<Option<Token>> {
+        Ok(self.tokens.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &str, token: &Token) -> Result<()> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), token.clone());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        self.tokens.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// TokenStore keeping each token in its own file in a directory.
+///
+/// Files are named by the SHA-256 hash of the key and replaced atomically,
+/// so the directory can be shared between processes. With the encryption
+/// feature the tokens can be encrypted at rest with AES-256-GCM.
+pub struct FileTokenStore {
+    dir: PathBuf,
+    #[cfg(feature = "encryption")]
+    key: Option<Zeroizing<[u8; 32]>>,
+}
+
+impl fmt::Debug for FileTokenStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("FileTokenStore");
+        debug.field("dir", &self.dir);
+        #[cfg(feature = "encryption")]
+        debug.field("key", &crate::redact::redact_option(&self.key));
+        debug.finish()
+    }
+}
+
+impl FileTokenStore {
+    /// Creates new FileTokenStore, creating the directory if needed.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<FileTokenStore> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| store_error(&dir, e))?;
+        Ok(FileTokenStore {
+            dir,
+            #[cfg(feature = "encryption")]
+            key: None,
+        })
+    }
+
+    /// Encrypts the stored tokens with the given 256-bit key.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption_key(mut self, key: [u8; 32]) -> FileTokenStore {
+        self.key = Some(Zeroizing::new(key));
+        self
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let name: String = Sha256::digest(key.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(format!("{}.token", name))
+    }
+
+    #[cfg(feature = "encryption")]
+    fn encode(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        use aes_gcm::aead::{Aead, KeyInit};
+        use aes_gcm::{Aes256Gcm, Nonce};
+
+        let key = match &self.key {
+            Some(key) => key,
+            None => return Ok(data),
+        };
+        let nonce: [u8; 12] = rand::thread_rng().gen();
+        let cipher = Aes256Gcm::new_from_slice(&key[..]).expect("key length is 32");
+        let mut encrypted = nonce.to_vec();
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), data.as_slice())
+            .map_err(|_| Error::TokenStore(String::from("failed to encrypt token")))?;
+        encrypted.extend(ciphertext);
+        Ok(encrypted)
+    }
+
+    #[cfg(feature = "encryption")]
+    fn decode(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        use aes_gcm::aead::{Aead, KeyInit};
+        use aes_gcm::{Aes256Gcm, Nonce};
+
+        let key = match &self.key {
+            Some(key) => key,
+            None => return Ok(data),
+        };
+        if data.len() < 12 {
+            return Err(Error::TokenStore(String::from(
+                "encrypted token is truncated",
+            )));
+        }
+        let cipher = Aes256Gcm::new_from_slice(&key[..]).expect("key length is 32");
+        let (nonce, ciphertext) = data.split_at(12);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::TokenStore(String::from("failed to decrypt token")))
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn encode(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        Ok(data)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn decode(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        Ok(data)
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self, key: &str) -> Result<Option<Token>> {
+        let path = self.path(key);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(store_error(&path, e)),
+        };
+        let data = self.decode(data)?;
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| Error::TokenStore(format!("invalid token in {}: {}", path.display(), e)))
+    }
+
+    fn save(&self, key: &str, token: &Token) -> Result<()> {
+        let path = self.path(key);
+        let data = serde_json::to_vec(token)
+            .map_err(|e| Error::TokenStore(format!("failed to serialize token: {}", e)))?;
+        let data = self.encode(data)?;
+
+        // Write to a temporary file first so readers never see partial tokens
+        let suffix: u64 = rand::thread_rng().gen();
+        let tmp = path.with_extension(format!("tmp{}", suffix));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp).map_err(|e| store_error(&tmp, e))?;
+        file.write_all(&data)
+            .and_then(|_| file.sync_all())
+            .and_then(|_| fs::rename(&tmp, &path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp);
+                store_error(&path, e)
+            })
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(store_error(&path, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn store_error(path: &Path, e: std::io::Error) -> Error {
+    Error::TokenStore(format!("{}: {}", path.display(), e))
+}
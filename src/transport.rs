@@ -0,0 +1,125 @@
+use crate::error::Result;
+use crate::redact::Headers;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, StatusCode, Url};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::delay_for;
+
+/// HTTP request sent through a transport.
+#[derive(Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+    /// Timeout for the whole request, from connecting until the response
+    /// body has been read.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Creates new HttpRequest without headers or body.
+    pub fn new(method: Method, url: Url) -> HttpRequest {
+        HttpRequest {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+}
+
+/// Credentials are masked and only the length of the body is shown, as
+/// form bodies can contain client secrets.
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url.as_str())
+            .field("headers", &Headers(&self.headers))
+            .field("body", &self.body.as_ref().map(|b| b.len()))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// HTTP response received through a transport, with the body fully read.
+#[derive(Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Creates new HttpResponse.
+    pub fn new(status: StatusCode, headers: HeaderMap, body: Vec<u8>) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    /// Returns the body as text, replacing invalid UTF-8 sequences.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+impl fmt::Debug for HttpResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpResponse")
+            .field("status", &self.status)
+            .field("headers", &Headers(&self.headers))
+            .field("body", &self.body.len())
+            .finish()
+    }
+}
+
+/// Sends HTTP requests for the clients.
+///
+/// reqwest::Client implements this and is used by default. Other
+/// implementations can be set with OptionsBuilder::transport, for example
+/// to use another TLS stack or runtime, or an in-process test double.
+///
+/// Transports return Error::Transport or Error::Timeout when the request
+/// could not be completed. Unsuccessful HTTP statuses are returned as
+/// responses; the clients turn them into errors.
+///
+/// Retry backoff and rate limiting wait with HttpTransport::sleep. The
+/// default uses the tokio 0.2 timer, so transports running on another
+/// runtime must override it.
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    /// Sends the request and reads the whole response.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+
+    /// Waits for the given duration between requests.
+    async fn sleep(&self, duration: Duration) {
+        delay_for(duration).await;
+    }
+}
+
+#[async_trait]
+impl HttpTransport for Client {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+        Ok(HttpResponse::new(status, headers, body))
+    }
+}
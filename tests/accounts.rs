@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::get_first)]
 mod accounts_tests {
     use op_api_sdk::apis::accounts::*;
     use op_api_sdk::options::Options;
@@ -15,8 +16,7 @@ mod accounts_tests {
     #[tokio::test]
     async fn test_accounts() {
         init();
-        let mut options = options();
-        options.set_version("v3".to_string());
+        let options = options();
         let client = Accounts::new(options);
 
         // First test getting all accounts
@@ -30,8 +30,8 @@ mod accounts_tests {
             Some(account) => {
                 assert_eq!(false, account.account_id.is_empty());
                 assert_eq!(false, account.name.is_empty());
-                assert_eq!(false, account.currency.is_empty());
-                assert_eq!(false, account.identifier_scheme.is_empty());
+                assert_eq!(3, account.currency.code().len());
+                assert_eq!(IdentifierScheme::Iban, account.identifier_scheme);
                 assert_eq!(false, account.identifier.is_empty());
                 assert_eq!(false, account.servicer_scheme.is_empty());
                 assert_eq!(false, account.servicer_identifier.is_empty());
@@ -63,12 +63,7 @@ mod accounts_tests {
         );
 
         // Then try to fetch transactions
-        let params = TransactionParams {
-            forward_paging_token: None,
-            from_booking_datetime: None,
-            page_size: Some(5),
-            to_booking_datetime: None,
-        };
+        let params = TransactionParams::builder().page_size(5).build().unwrap();
         let trans_resp = client
             .transactions(original_account.account_id.clone(), Some(params))
             .await;
@@ -79,7 +74,7 @@ mod accounts_tests {
 
         for trans in transactions.transactions.iter() {
             assert_eq!(false, trans.transaction_id.is_empty());
-            assert_eq!(false, trans.amount.is_empty());
+            assert_eq!(trans.account_balance.currency(), trans.amount.currency());
         }
     }
 }
@@ -0,0 +1,146 @@
+mod common;
+
+#[cfg(test)]
+mod auth_tests {
+    use super::common::{builder, MockResponse, MockServer};
+    use chrono::{Duration, Utc};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::auth::*;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::Error;
+    use reqwest::Client;
+
+    const ACCOUNTS: &str = r#"{"accounts":[]}"#;
+
+    fn config(server: &MockServer) -> OAuthConfig {
+        let mut config = OAuthConfig::new(&format!("{}/oauth/token", server.url()), "client");
+        config.set_client_secret("secret");
+        config.set_scope("accounts");
+        config
+    }
+
+    fn options(server: &MockServer, authenticator: Authenticator) -> Options {
+        builder(server)
+            .authenticator(authenticator)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials() {
+        let token_server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"access_token":"first","token_type":"Bearer","expires_in":3600}"#,
+        )]);
+        let api = MockServer::start(vec![
+            MockResponse::json(200, ACCOUNTS),
+            MockResponse::json(200, ACCOUNTS),
+        ]);
+        let authenticator = Authenticator::client_credentials(config(&token_server));
+        let client = Accounts::new(options(&api, authenticator.clone()));
+        assert!(client.accounts().await.is_ok());
+        assert!(client.accounts().await.is_ok());
+
+        // Token is cached between requests
+        let token_requests = token_server.requests();
+        assert_eq!(1, token_requests.len());
+        assert_eq!("/oauth/token", token_requests[0].path);
+        assert_eq!(
+            "client_id=client&client_secret=secret&grant_type=client_credentials&scope=accounts",
+            token_requests[0].body
+        );
+        for request in api.requests() {
+            assert_eq!(Some("Bearer first"), request.header("authorization"));
+        }
+        let token = authenticator.token().await.unwrap();
+        assert_eq!("first", token.access_token);
+        assert!(!token.is_expired(Duration::seconds(30)));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_expired_token() {
+        let token_server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"access_token":"second","expires_in":3600}"#,
+        )]);
+        let api = MockServer::start(vec![MockResponse::json(200, ACCOUNTS)]);
+        let mut token = Token::new("first");
+        token.refresh_token = Some(String::from("refresh"));
+        token.expires_at = Some(Utc::now() - Duration::seconds(1));
+        let authenticator = Authenticator::with_token(config(&token_server), token);
+        let client = Accounts::new(options(&api, authenticator.clone()));
+        assert!(client.accounts().await.is_ok());
+
+        assert_eq!(
+            "client_id=client&client_secret=secret&grant_type=refresh_token&refresh_token=refresh",
+            token_server.requests()[0].body
+        );
+        assert_eq!(
+            Some("Bearer second"),
+            api.requests()[0].header("authorization")
+        );
+        // Refresh token is kept when the endpoint does not rotate it
+        let token = authenticator.token().await.unwrap();
+        assert_eq!(Some(String::from("refresh")), token.refresh_token);
+    }
+
+    #[tokio::test]
+    async fn test_retry_unauthorized() {
+        let token_server = MockServer::start(vec![
+            MockResponse::json(200, r#"{"access_token":"revoked","expires_in":3600}"#),
+            MockResponse::json(200, r#"{"access_token":"valid","expires_in":3600}"#),
+        ]);
+        let api = MockServer::start(vec![
+            MockResponse::new(401, "Unauthorized"),
+            MockResponse::json(200, ACCOUNTS),
+        ]);
+        let authenticator = Authenticator::client_credentials(config(&token_server));
+        let client = Accounts::new(options(&api, authenticator));
+        let resp = client.accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+
+        let requests = api.requests();
+        assert_eq!(Some("Bearer revoked"), requests[0].header("authorization"));
+        assert_eq!(Some("Bearer valid"), requests[1].header("authorization"));
+    }
+
+    #[tokio::test]
+    async fn test_token_errors() {
+        let token_server = MockServer::start(vec![
+            MockResponse::json(
+                400,
+                r#"{"error":"invalid_grant","error_description":"Code expired"}"#,
+            ),
+            MockResponse::new(500, "oops"),
+        ]);
+        let grant = Grant::AuthorizationCode {
+            code: String::from("code"),
+            redirect_uri: String::from("https://localhost/callback"),
+            code_verifier: None,
+        };
+        let config = config(&token_server);
+        match request_token(&Client::new(), &config, &grant).await {
+            Err(e @ Error::Auth { .. }) => assert_eq!(
+                "Authentication failed: invalid_grant (Code expired)",
+                e.to_string()
+            ),
+            other => panic!("Expected auth error, got {:?}", other),
+        }
+        match request_token(&Client::new(), &config, &grant).await {
+            Err(Error::Auth {
+                error, description, ..
+            }) => {
+                assert_eq!("HTTP 500 Internal Server Error", error);
+                assert_eq!(Some(String::from("oops")), description);
+            }
+            other => panic!("Expected auth error, got {:?}", other),
+        }
+
+        // Expired token without refresh token cannot be renewed
+        let mut token = Token::new("old");
+        token.expires_at = Some(Utc::now());
+        let authenticator = Authenticator::with_token(config, token);
+        let resp = authenticator.access_token(&Client::new()).await;
+        assert!(matches!(resp, Err(Error::Auth { .. })), "{:?}", resp);
+    }
+}
@@ -0,0 +1,115 @@
+mod common;
+
+#[cfg(test)]
+mod authorization_tests {
+    use super::common::{MockResponse, MockServer};
+    use op_api_sdk::auth::OAuthConfig;
+    use op_api_sdk::authorization::*;
+    use op_api_sdk::Error;
+    use reqwest::{Client, Url};
+    use std::collections::HashMap;
+
+    fn flow(token_url: &str) -> AuthorizationFlow {
+        let mut config = OAuthConfig::new(token_url, "client");
+        config.set_scope("openid accounts");
+        AuthorizationFlow::new(
+            "https://auth.example.com/oauth/authorize",
+            config,
+            "https://my.app/callback",
+        )
+    }
+
+    #[test]
+    fn test_pkce() {
+        // Test vector from RFC 7636 appendix B
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        assert_eq!(
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM",
+            pkce.challenge
+        );
+
+        let first = Pkce::new();
+        let second = Pkce::new();
+        assert_eq!(64, first.verifier.len());
+        assert_ne!(first.verifier, second.verifier);
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let flow = flow("https://auth.example.com/oauth/token");
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        let request = flow.start_with("state123", &pkce).unwrap();
+        assert_eq!("state123", request.state);
+        assert_eq!(pkce.verifier, request.code_verifier);
+
+        let url = Url::parse(&request.url).unwrap();
+        assert_eq!("/oauth/authorize", url.path());
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!("code", params["response_type"]);
+        assert_eq!("client", params["client_id"]);
+        assert_eq!("https://my.app/callback", params["redirect_uri"]);
+        assert_eq!("state123", params["state"]);
+        assert_eq!(pkce.challenge, params["code_challenge"]);
+        assert_eq!("S256", params["code_challenge_method"]);
+        assert_eq!("openid accounts", params["scope"]);
+
+        let first = flow.start().unwrap();
+        let second = flow.start().unwrap();
+        assert_ne!(first.state, second.state);
+    }
+
+    #[test]
+    fn test_callback_validation() {
+        let flow = flow("https://auth.example.com/oauth/token");
+        let request = flow.start_with("state123", &Pkce::new()).unwrap();
+
+        let code = flow
+            .parse_callback(&request, "https://my.app/callback?code=abc&state=state123")
+            .unwrap();
+        assert_eq!("abc", code);
+
+        match flow.parse_callback(&request, "https://my.app/callback?code=abc&state=other") {
+            Err(Error::Auth { error, .. }) => assert_eq!("invalid_state", error),
+            other => panic!("Expected auth error, got {:?}", other),
+        }
+
+        let denied =
+            "https://my.app/callback?error=access_denied&error_description=No&state=state123";
+        match flow.parse_callback(&request, denied) {
+            Err(Error::Auth {
+                error, description, ..
+            }) => {
+                assert_eq!("access_denied", error);
+                assert_eq!(Some(String::from("No")), description);
+            }
+            other => panic!("Expected auth error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code() {
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"access_token":"access","refresh_token":"refresh","expires_in":600}"#,
+        )]);
+        let flow = flow(&format!("{}/oauth/token", server.url()));
+        let pkce = Pkce::from_verifier("verifier-0123456789-0123456789-0123456789");
+        let request = flow.start_with("state123", &pkce).unwrap();
+        let authenticator = flow
+            .finish(
+                &Client::new(),
+                &request,
+                "https://my.app/callback?code=abc&state=state123",
+            )
+            .await
+            .unwrap();
+
+        let token = authenticator.token().await.unwrap();
+        assert_eq!("access", token.access_token);
+        assert_eq!(Some(String::from("refresh")), token.refresh_token);
+        assert_eq!(
+            "client_id=client&grant_type=authorization_code&code=abc&redirect_uri=https%3A%2F%2Fmy.app%2Fcallback&code_verifier=verifier-0123456789-0123456789-0123456789",
+            server.requests()[0].body
+        );
+    }
+}
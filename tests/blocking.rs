@@ -0,0 +1,112 @@
+#![cfg(feature = "blocking")]
+mod common;
+
+#[cfg(test)]
+mod blocking_tests {
+    use super::common::{options, MockResponse, MockServer};
+    use op_api_sdk::blocking::Accounts;
+    use op_api_sdk::Error;
+    use std::thread;
+
+    const ACCOUNT: &str = r#"{
+        "accountId": "a1",
+        "name": "Current account",
+        "currency": "EUR",
+        "identifierScheme": "IBAN",
+        "identifier": "FI0000000000000001",
+        "servicerScheme": "BIC",
+        "servicerIdentifier": "OKOYFIHH"
+    }"#;
+
+    fn client(server: &MockServer) -> Accounts {
+        Accounts::new(options(server))
+    }
+
+    #[test]
+    fn test_accounts() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, &format!(r#"{{"accounts":[{}]}}"#, ACCOUNT)),
+            MockResponse::json(200, ACCOUNT),
+            MockResponse::json(200, r#"{"transactions":[],"_links":{}}"#),
+            MockResponse::json(404, r#"{"errors":[]}"#),
+        ]);
+        let client = client(&server);
+        assert_eq!("v3", client.version());
+
+        let accounts = client.accounts().unwrap();
+        assert_eq!("a1", accounts.accounts[0].account_id);
+        let account = client.account(String::from("a1")).unwrap();
+        assert_eq!("Current account", account.name);
+        let transactions = client.transactions(String::from("a1"), None).unwrap();
+        assert!(transactions.transactions.is_empty());
+        let resp = client.with_version("v2").account(String::from("a2"));
+        assert_eq!(
+            Some(404),
+            resp.err().and_then(|e| e.status()).map(|s| s.as_u16())
+        );
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!("/accounts/v2/accounts/a2", paths[3]);
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let response = MockResponse::json(200, r#"{"accounts":[]}"#);
+        let server = MockServer::start(vec![response; 4]);
+        let client = client(&server);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let client = client.clone();
+                thread::spawn(move || client.accounts())
+            })
+            .collect();
+        for handle in handles {
+            let resp = handle.join().unwrap();
+            assert!(resp.is_ok(), "{:?}", resp.err());
+        }
+        assert_eq!(4, server.requests().len());
+    }
+
+    #[test]
+    fn test_errors() {
+        let server = MockServer::start(vec![MockResponse::json(200, "not json")]);
+        match client(&server).accounts() {
+            Err(Error::Deserialize { body, .. }) => assert_eq!("not json", body),
+            other => panic!("Expected deserialize error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_transactions_iter() {
+        let transaction = |id: &str| {
+            format!(
+                r#"{{"transactionId":"{}","accountId":"a1","amount":"1.00","currency":"EUR",
+                "creditDebitIndicator":"credit","accountBalance":"1.00",
+                "bookingDateTime":"2020-10-01T12:00:00Z","valueDateTime":"2020-10-01T12:00:00Z"}}"#,
+                id
+            )
+        };
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                200,
+                &format!(
+                    r#"{{"transactions":[{}],"_links":{{"next":{{"href":"/next?forwardPagingToken=p2"}}}}}}"#,
+                    transaction("t1")
+                ),
+            ),
+            MockResponse::json(
+                200,
+                &format!(
+                    r#"{{"transactions":[{}],"_links":{{}}}}"#,
+                    transaction("t2")
+                ),
+            ),
+        ]);
+        let ids: Vec<String> = client(&server)
+            .transactions_iter(String::from("a1"), None)
+            .map(|t| t.unwrap().transaction_id)
+            .collect();
+        assert_eq!(vec!["t1", "t2"], ids);
+        assert_eq!(2, server.requests().len());
+    }
+}
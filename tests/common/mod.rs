@@ -0,0 +1,336 @@
+//! Minimal HTTP server for testing clients without the real API.
+#![allow(dead_code)]
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use op_api_sdk::options::{Options, OptionsBuilder};
+use op_api_sdk::retry::RetryPolicy;
+use op_api_sdk::Error;
+use openssl::ssl::SslAcceptor;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Options sending requests to the server without retries.
+pub fn options(server: &MockServer) -> Options {
+    let mut options = Options::new_dev(String::from("test-key"));
+    options.set_base_url(server.url().to_string());
+    options.set_retry_policy(RetryPolicy::none());
+    options
+}
+
+/// Same as options, for settings only available in OptionsBuilder.
+pub fn builder(server: &MockServer) -> OptionsBuilder {
+    Options::builder()
+        .api_key("test-key")
+        .base_url(server.url())
+        .retry_policy(RetryPolicy::none())
+}
+
+/// Returns the message of the expected configuration error.
+pub fn config_error<T>(result: Result<T, Error>) -> String {
+    match result {
+        Err(Error::Config(msg)) => msg,
+        Err(e) => panic!("Expected configuration error, got {:?}", e),
+        Ok(_) => panic!("Expected configuration error, got Ok"),
+    }
+}
+
+/// Canned response returned by the MockServer.
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: &str) -> MockResponse {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: body.to_string(),
+            delay: None,
+        }
+    }
+
+    pub fn json(status: u16, body: &str) -> MockResponse {
+        MockResponse::new(status, body).header("Content-Type", "application/json")
+    }
+
+    pub fn delay(mut self, delay: Duration) -> MockResponse {
+        self.delay = Some(delay);
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> MockResponse {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Request received by the MockServer.
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(|v| v.as_str())
+    }
+}
+
+/// Serves given responses in order, one per connection unless started
+/// with start_keep_alive.
+pub struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    pub fn start(responses: Vec<MockResponse>) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                accepted.fetch_add(1, Ordering::SeqCst);
+                handle(stream, &response, &recorded);
+            }
+        });
+        MockServer {
+            url,
+            requests,
+            connections,
+        }
+    }
+
+    /// Serves given responses in order, keeping connections open for
+    /// further requests until the client closes them or they are idle.
+    pub fn start_keep_alive(responses: Vec<MockResponse>) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        thread::spawn(move || {
+            let mut responses = responses.into_iter().peekable();
+            while responses.peek().is_some() {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                accepted.fetch_add(1, Ordering::SeqCst);
+                // Idle connections are dropped so that new ones are served
+                let _ = stream.set_read_timeout(Some(Duration::from_millis(500)));
+                let mut reader = BufReader::new(stream);
+                while let Some(response) = responses.peek() {
+                    if !read_request(&mut reader, &recorded) {
+                        break;
+                    }
+                    write_response(reader.get_mut(), response, true);
+                    responses.next();
+                }
+            }
+        });
+        MockServer {
+            url,
+            requests,
+            connections,
+        }
+    }
+
+    /// Serves given responses over TLS. Connections failing the handshake
+    /// do not consume responses.
+    pub fn start_tls(responses: Vec<MockResponse>, acceptor: SslAcceptor) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "https://localhost:{}",
+            listener.local_addr().unwrap().port()
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        thread::spawn(move || {
+            let mut responses = responses.into_iter();
+            let mut next = responses.next();
+            while let Some(response) = next.as_ref() {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                accepted.fetch_add(1, Ordering::SeqCst);
+                if let Ok(stream) = acceptor.accept(stream) {
+                    handle(stream, response, &recorded);
+                    next = responses.next();
+                }
+            }
+        });
+        MockServer {
+            url,
+            requests,
+            connections,
+        }
+    }
+
+    /// Base URL of the server without trailing slash.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Number of accepted TCP connections.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+}
+
+fn handle<S: Read + Write>(
+    stream: S,
+    response: &MockResponse,
+    recorded: &Mutex<Vec<RecordedRequest>>,
+) {
+    let mut reader = BufReader::new(stream);
+    if read_request(&mut reader, recorded) {
+        write_response(reader.get_mut(), response, false);
+    }
+}
+
+/// Reads and records one request. Returns false if the connection was
+/// closed before a request was received.
+fn read_request<S: Read>(
+    reader: &mut BufReader<S>,
+    recorded: &Mutex<Vec<RecordedRequest>>,
+) -> bool {
+    let mut line = String::new();
+    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+        return false;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+    recorded.lock().unwrap().push(RecordedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    });
+    true
+}
+
+fn write_response<S: Write>(stream: &mut S, response: &MockResponse, keep_alive: bool) {
+    if let Some(delay) = response.delay {
+        thread::sleep(delay);
+    }
+    let mut out = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in response.headers.iter() {
+        out.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    let connection = if keep_alive { "keep-alive" } else { "close" };
+    out.push_str(&format!(
+        "Content-Length: {}\r\nConnection: {}\r\n\r\n{}",
+        response.body.len(),
+        connection,
+        response.body
+    ));
+    let _ = stream.write_all(out.as_bytes());
+    let _ = stream.flush();
+}
+
+/// Logger capturing messages for assertions.
+pub struct CaptureLogger {
+    messages: Mutex<Vec<(Level, String)>>,
+}
+
+impl CaptureLogger {
+    /// Returns the captured messages of the given level.
+    pub fn messages(&self, level: Level) -> Vec<String> {
+        self.messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(l, _)| *l == level)
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+
+    /// Returns all captured messages.
+    pub fn all_messages(&self) -> Vec<String> {
+        let messages = self.messages.lock().unwrap();
+        messages
+            .iter()
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        self.messages
+            .lock()
+            .unwrap()
+            .push((record.level(), format!("{}", record.args())));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger {
+    messages: Mutex::new(Vec::new()),
+};
+
+/// Installs the capturing logger and returns it.
+///
+/// Only one logger can be installed per test binary, so all tests of the
+/// binary share it.
+pub fn capture_logs() -> &'static CaptureLogger {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+    &LOGGER
+}
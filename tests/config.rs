@@ -0,0 +1,122 @@
+mod common;
+
+#[cfg(test)]
+mod config_tests {
+    use super::common::config_error;
+    use op_api_sdk::config::*;
+    use op_api_sdk::options::Options;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("op-api-sdk-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    // Environment is process wide so all cases are run in a single test
+    #[test]
+    fn test_from_env() {
+        let vars = [
+            ENV_API_KEY,
+            ENV_AUTHORIZATION,
+            ENV_ENVIRONMENT,
+            ENV_BASE_URL,
+            ENV_API_VERSIONS,
+            ENV_TIMEOUT,
+            ENV_CONNECT_TIMEOUT,
+        ];
+        for var in vars.iter() {
+            env::remove_var(var);
+        }
+        let msg = config_error(Options::from_env());
+        assert_eq!("environment variable X_API_KEY is not set", msg);
+
+        env::set_var(ENV_API_KEY, "key");
+        let options = Options::from_env().unwrap();
+        assert_eq!("key", options.api_key());
+        assert_eq!("https://sandbox.apis.op-palvelut.fi", options.base_url());
+
+        env::set_var(ENV_ENVIRONMENT, "production");
+        env::set_var(ENV_AUTHORIZATION, "token");
+        env::set_var(ENV_API_VERSIONS, "accounts=v3, mobility=v1");
+        env::set_var(ENV_TIMEOUT, "15");
+        let options = Options::from_env().unwrap();
+        assert_eq!("https://prod.apis.op-palvelut.fi", options.base_url());
+        assert_eq!("token", options.authorization());
+        assert_eq!(Some("v3"), options.api_version("accounts"));
+        assert_eq!(Some("v1"), options.api_version("mobility"));
+        assert_eq!(Some(Duration::from_secs(15)), options.timeout());
+
+        env::set_var(ENV_TIMEOUT, "soon");
+        assert_eq!(
+            "invalid OP_TIMEOUT_SECS: soon",
+            config_error(Options::from_env())
+        );
+        env::remove_var(ENV_TIMEOUT);
+
+        env::set_var(ENV_API_VERSIONS, "accounts");
+        let msg = config_error(Options::from_env());
+        assert_eq!("invalid OP_API_VERSIONS entry: accounts", msg);
+        env::remove_var(ENV_API_VERSIONS);
+
+        env::set_var(ENV_ENVIRONMENT, "staging");
+        assert_eq!(
+            "invalid environment: staging",
+            config_error(Options::from_env())
+        );
+
+        for var in vars.iter() {
+            env::remove_var(var);
+        }
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = write_file(
+            "options.toml",
+            r#"
+api_key = "toml-key"
+base_url = "http://localhost:8080/"
+timeout_secs = 5
+
+[api_versions]
+accounts = "v3"
+"#,
+        );
+        let options = Options::from_file(&path).unwrap();
+        assert_eq!("toml-key", options.api_key());
+        assert_eq!("http://localhost:8080", options.base_url());
+        assert_eq!(Some("v3"), options.api_version("accounts"));
+        assert_eq!(Some(Duration::from_secs(5)), options.timeout());
+        fs::remove_file(path).unwrap();
+
+        let path = write_file(
+            "options.json",
+            r#"{"api_key": "json-key", "environment": "sandbox", "api_versions": {"accounts": "v2"}}"#,
+        );
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(
+            Some("json-key"),
+            config.api_key.as_ref().map(|k| k.as_str())
+        );
+        let options = config.build().unwrap();
+        assert_eq!(Some("v2"), options.api_version("accounts"));
+        fs::remove_file(path).unwrap();
+
+        let path = write_file("invalid.toml", "api_key = \"key\"\nunknown = 1\n");
+        let msg = config_error(Options::from_file(&path));
+        assert!(msg.contains("unknown field"), "{}", msg);
+        fs::remove_file(path).unwrap();
+
+        let path = write_file("options.yaml", "api_key: key");
+        let msg = config_error(Options::from_file(&path));
+        assert!(msg.starts_with("unsupported config file format"), "{}", msg);
+        fs::remove_file(path).unwrap();
+
+        let msg = config_error(Options::from_file("/nonexistent/options.toml"));
+        assert!(msg.starts_with("failed to read"), "{}", msg);
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod error_tests {
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::Error;
+
+    #[tokio::test]
+    async fn test_config_error() {
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(String::new());
+        let client = Accounts::new(options);
+        let resp = client.accounts().await;
+        match resp {
+            Err(Error::Config(msg)) => assert_eq!("base URL is not set", msg),
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+
+        let client = Accounts::new(Options::new_dev(String::new()));
+        let resp = client.accounts().await;
+        match resp {
+            Err(e @ Error::Config(_)) => {
+                assert_eq!(None, e.status());
+                assert_eq!("Invalid configuration: API key is not set", e.to_string());
+            }
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+    }
+}
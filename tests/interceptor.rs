@@ -0,0 +1,178 @@
+mod common;
+
+#[cfg(test)]
+mod interceptor_tests {
+    use super::common::{capture_logs, options, MockResponse, MockServer};
+    use log::Level;
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::interceptor::{HeaderInterceptor, Interceptor, LoggingInterceptor};
+    use op_api_sdk::options::Options;
+    use op_api_sdk::retry::RetryPolicy;
+    use op_api_sdk::transport::{HttpRequest, HttpResponse};
+    use op_api_sdk::{Error, Result};
+    use std::sync::{Arc, Mutex};
+
+    /// Records the calls to the interceptor with its name.
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn before_request(&self, request: &mut HttpRequest) -> Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("{} before {}", self.name, request.url.path()));
+            Ok(())
+        }
+
+        fn after_response(
+            &self,
+            _request: &HttpRequest,
+            response: &mut HttpResponse,
+        ) -> Result<()> {
+            self.calls.lock().unwrap().push(format!(
+                "{} after {}",
+                self.name,
+                response.status.as_u16()
+            ));
+            Ok(())
+        }
+
+        fn on_error(&self, _request: &HttpRequest, _error: &Error) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("{} error", self.name));
+        }
+    }
+
+    /// Replaces the response body.
+    struct Rewrite;
+
+    impl Interceptor for Rewrite {
+        fn after_response(
+            &self,
+            _request: &HttpRequest,
+            response: &mut HttpResponse,
+        ) -> Result<()> {
+            response.body = br#"{"accounts":[]}"#.to_vec();
+            Ok(())
+        }
+    }
+
+    /// Rejects all requests.
+    struct Reject;
+
+    impl Interceptor for Reject {
+        fn before_request(&self, _request: &mut HttpRequest) -> Result<()> {
+            Err(Error::Config(String::from("rejected")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_order() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+            MockResponse::json(500, r#"{"errors":[]}"#),
+        ]);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut options = options(&server);
+        for name in ["first", "second"].iter() {
+            options.add_interceptor(Arc::new(Recorder {
+                name,
+                calls: calls.clone(),
+            }));
+        }
+        let client = Accounts::new(options);
+        assert!(client.accounts().await.is_ok());
+        assert!(client.accounts().await.is_err());
+
+        let expected = vec![
+            "first before /accounts/v3/accounts",
+            "second before /accounts/v3/accounts",
+            "second after 200",
+            "first after 200",
+            "first before /accounts/v3/accounts",
+            "second before /accounts/v3/accounts",
+            "second after 500",
+            "first after 500",
+        ];
+        assert_eq!(expected, *calls.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_transport_error() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let options = Options::builder()
+            .api_key("test-key")
+            .base_url("http://127.0.0.1:1")
+            .retry_policy(RetryPolicy::none())
+            .interceptor(Arc::new(Recorder {
+                name: "recorder",
+                calls: calls.clone(),
+            }))
+            .build()
+            .unwrap();
+        let resp = Accounts::new(options).accounts().await;
+        assert!(matches!(resp, Err(Error::Transport(_))), "{:?}", resp);
+        assert_eq!(
+            vec!["recorder before /accounts/v3/accounts", "recorder error"],
+            *calls.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_request_and_response() {
+        let server = MockServer::start(vec![MockResponse::json(200, "not json")]);
+        let mut headers = HeaderInterceptor::new();
+        headers.set_header("x-correlation-id", "abc-123").unwrap();
+        headers.set_header("x-api-key", "override").unwrap();
+        let mut options = options(&server);
+        options.add_interceptor(Arc::new(headers));
+        options.add_interceptor(Arc::new(Rewrite));
+
+        let resp = Accounts::new(options).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        let request = &server.requests()[0];
+        assert_eq!(Some("abc-123"), request.header("x-correlation-id"));
+        assert_eq!(Some("override"), request.header("x-api-key"));
+    }
+
+    #[tokio::test]
+    async fn test_reject_request() {
+        let server = MockServer::start(vec![MockResponse::json(200, r#"{"accounts":[]}"#)]);
+        let mut options = options(&server);
+        options.add_interceptor(Arc::new(Reject));
+        let resp = Accounts::new(options).accounts().await;
+        assert!(matches!(resp, Err(Error::Config(_))), "{:?}", resp);
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let mut headers = HeaderInterceptor::new();
+        assert!(headers.set_header("bad header", "value").is_err());
+        assert!(headers.set_header("x-header", "bad\nvalue").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_logging_interceptor() {
+        let logger = capture_logs();
+
+        let server = MockServer::start(vec![MockResponse::json(200, r#"{"accounts":[]}"#)]);
+        let mut logging = LoggingInterceptor::default();
+        logging.set_headers(true);
+        let mut options = options(&server);
+        options.add_interceptor(Arc::new(logging));
+        assert!(Accounts::new(options).accounts().await.is_ok());
+
+        let messages = logger.messages(Level::Info);
+        assert_eq!(2, messages.len());
+        let url = format!("{}/accounts/v3/accounts", server.url());
+        assert!(messages[0].starts_with(&format!("--> GET {}", url)));
+        assert!(messages[0].contains(r#""x-api-key": [REDACTED]"#));
+        assert!(messages[1].starts_with(&format!("<-- 200 OK GET {}", url)));
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod models_tests {
+    use op_api_sdk::apis::accounts::*;
+    use op_api_sdk::money::Money;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::Value;
+    use std::fmt::Debug;
+
+    const ACCOUNTS: &str = include_str!("fixtures/accounts/accounts.json");
+    const TRANSACTIONS: &str = include_str!("fixtures/accounts/transactions.json");
+
+    /// Deserializes the fixture, serializes it back and checks that the
+    /// result deserializes to an equal value.
+    fn round_trip<T: Serialize + DeserializeOwned + Clone + PartialEq + Debug>(
+        json: &str,
+    ) -> (T, Value) {
+        let model: T = serde_json::from_str(json).unwrap();
+        let serialized = serde_json::to_value(&model).unwrap();
+        let parsed: T = serde_json::from_value(serialized.clone()).unwrap();
+        assert_eq!(model, parsed);
+        assert_eq!(model, model.clone());
+        (model, serialized)
+    }
+
+    const TRANSACTION: &str = r#"{
+        "transactionId": "t1",
+        "accountId": "a1",
+        "amount": "-12.50",
+        "currency": "EUR",
+        "creditDebitIndicator": "debit",
+        "accountBalance": "100.00",
+        "creditor": {
+            "accountIdentifierType": "IBAN",
+            "accountName": "Shop",
+            "accountIdentifier": "FI0000000000000002",
+            "servicerIdentifier": "OKOYFIHH",
+            "servicerIdentifierType": "BIC"
+        },
+        "bookingDateTime": "2020-10-01T12:00:00Z",
+        "valueDateTime": "2020-10-01T12:00:00Z",
+        "status": "AwaitingAuthorisation"
+    }"#;
+
+    #[test]
+    fn test_enums() {
+        let transaction: Transaction = serde_json::from_str(TRANSACTION).unwrap();
+        assert_eq!(
+            CreditDebitIndicator::Debit,
+            transaction.credit_debit_indicator
+        );
+        assert_eq!(
+            Some(TransactionStatus::AwaitingAuthorisation),
+            transaction.status
+        );
+        let creditor = transaction.creditor.unwrap();
+        assert_eq!(IdentifierScheme::Iban, creditor.account_identifier_type);
+
+        assert_eq!(CreditDebitIndicator::Credit, "credit".into());
+        assert_eq!("credit", CreditDebitIndicator::Credit.to_string());
+        assert_eq!(
+            r#""Revoked""#,
+            serde_json::to_string(&TransactionStatus::Revoked).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_enum_values() {
+        let json = TRANSACTION
+            .replace(r#""debit""#, r#""reversal""#)
+            .replace("AwaitingAuthorisation", "Booked")
+            .replace(r#""IBAN""#, r#""iban""#);
+        let transaction: Transaction = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            CreditDebitIndicator::Unknown(String::from("reversal")),
+            transaction.credit_debit_indicator
+        );
+        let status = transaction.status.unwrap();
+        assert_eq!(TransactionStatus::Unknown(String::from("Booked")), status);
+        assert_eq!("Booked", status.as_str());
+        // Known values in another case are kept as sent
+        assert_eq!(
+            r#""iban""#,
+            serde_json::to_string(&transaction.creditor.unwrap().account_identifier_type).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_accounts_round_trip() {
+        let (accounts, serialized) = round_trip::<AccountList>(ACCOUNTS);
+        assert_eq!(3, accounts.accounts.len());
+        assert_eq!(
+            Some(Money::parse("2456.83", "EUR").unwrap()),
+            accounts.accounts[0].balance
+        );
+        assert_eq!(None, accounts.accounts[1].balance);
+        assert_eq!(
+            Some(Money::parse("12345678901234567.89", "EUR").unwrap()),
+            accounts.accounts[2].balance
+        );
+
+        // Field names are kept and balances are serialized as exact numbers
+        let fixture: Value = serde_json::from_str(ACCOUNTS).unwrap();
+        assert_eq!(fixture, serialized);
+        let json = serialized.to_string();
+        assert!(json.contains(r#""balance":2456.83,"#), "{}", json);
+        assert!(
+            json.contains(r#""balance":12345678901234567.89,"#),
+            "{}",
+            json
+        );
+
+        let (account, _) = round_trip::<Account>(&serialized["accounts"][1].to_string());
+        assert_eq!(accounts.accounts[1], account);
+    }
+
+    #[test]
+    fn test_transactions_round_trip() {
+        let (transactions, serialized) = round_trip::<TransactionList>(TRANSACTIONS);
+        let fixture: Value = serde_json::from_str(TRANSACTIONS).unwrap();
+        assert_eq!(fixture, serialized);
+
+        let first = &transactions.transactions[0];
+        assert_eq!(Money::parse("-42.90", "EUR").unwrap(), first.amount);
+        assert_eq!(Some(TransactionStatus::Authorised), first.status);
+        let second = &transactions.transactions[1];
+        assert_eq!(
+            Some(TransactionStatus::Unknown(String::from("Booked"))),
+            second.status
+        );
+        assert!(transactions.links.next.is_some());
+
+        let mut changed = transactions.clone();
+        changed.transactions[1].message = Some(String::from("Salary"));
+        assert_ne!(transactions, changed);
+    }
+
+    #[test]
+    fn test_transaction_params_round_trip() {
+        let params = TransactionParams::builder().page_size(10).build().unwrap();
+        assert_eq!(params, params.clone());
+        assert_eq!(
+            r#"{"pageSize":10}"#,
+            serde_json::to_string(&params).unwrap()
+        );
+    }
+}
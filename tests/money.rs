@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod money_tests {
+    use op_api_sdk::apis::accounts::{Account, Transaction};
+    use op_api_sdk::money::{Currency, Decimal, Money};
+    use std::cmp::Ordering;
+    use std::str::FromStr;
+
+    fn eur(amount: &str) -> Money {
+        Money::parse(amount, "EUR").unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // 0.1 + 0.2 is exact unlike with f64
+        assert_eq!(eur("0.3"), eur("0.1") + eur("0.2"));
+        assert_eq!(eur("-12.40"), eur("0.10") - eur("12.50"));
+        assert_eq!(eur("12.5"), -eur("-12.50"));
+        assert_eq!(eur("12.50"), eur("-12.50").abs());
+        assert!(eur("-0.01").is_negative());
+        assert!(!eur("-0.00").is_negative());
+        assert!(Money::zero(Currency::eur()).is_zero());
+
+        let usd = Money::parse("1.00", "usd").unwrap();
+        assert_eq!("USD", usd.currency().code());
+        assert_eq!(None, eur("1.00").checked_add(&usd));
+        assert_eq!(None, eur("1.00").checked_sub(&usd));
+        assert_eq!(Some(eur("0.50")), eur("1.00").checked_sub(&eur("0.50")));
+        let max = Money::new(Decimal::MAX, Currency::eur());
+        assert_eq!(None, max.checked_add(&eur("1")));
+    }
+
+    #[test]
+    #[should_panic(expected = "currency mismatch")]
+    fn test_add_currency_mismatch() {
+        let _ = eur("1.00") + Money::parse("1.00", "SEK").unwrap();
+    }
+
+    #[test]
+    fn test_comparison() {
+        assert!(eur("10.00") > eur("9.99"));
+        assert!(eur("-1") < eur("0"));
+        assert_eq!(Some(Ordering::Equal), eur("1.0").partial_cmp(&eur("1.00")));
+        let sek = Money::parse("1.00", "SEK").unwrap();
+        assert_eq!(None, eur("1.00").partial_cmp(&sek));
+        assert!(!eur("2.00").gt(&sek) && !eur("2.00").lt(&sek));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            "1\u{a0}234\u{a0}567,80\u{a0}€",
+            eur("1234567.8").to_string()
+        );
+        assert_eq!("-12,50\u{a0}€", eur("-12.5").to_string());
+        assert_eq!("0,01\u{a0}€", eur("0.005").to_string());
+        assert_eq!("0,00\u{a0}€", eur("-0.001").to_string());
+        assert_eq!("123,00\u{a0}€", eur("123").to_string());
+        let jpy = Money::parse("1500", "JPY").unwrap();
+        assert_eq!("1\u{a0}500\u{a0}¥", jpy.to_string());
+        let sek = Money::parse("999.99", "SEK").unwrap();
+        assert_eq!("999,99\u{a0}SEK", sek.to_string());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Money::parse("12,50", "EUR").is_err());
+        assert!(Money::parse("12.50", "EURO").is_err());
+        assert!(Currency::new("E1R").is_err());
+        assert_eq!(Currency::eur(), Currency::from_str("eur").unwrap());
+    }
+
+    #[test]
+    fn test_serde() {
+        let money = eur("-12.50");
+        let json = serde_json::to_string(&money).unwrap();
+        assert_eq!(r#"{"amount":"-12.50","currency":"EUR"}"#, json);
+        assert_eq!(money, serde_json::from_str::<Money>(&json).unwrap());
+        let number: Money = serde_json::from_str(r#"{"amount":1234.56,"currency":"EUR"}"#).unwrap();
+        assert_eq!(Decimal::from_str("1234.56").unwrap(), number.amount());
+        let integer: Money = serde_json::from_str(r#"{"amount":-5,"currency":"EUR"}"#).unwrap();
+        assert_eq!(eur("-5"), integer);
+        assert!(serde_json::from_str::<Money>(r#"{"amount":"x","currency":"EUR"}"#).is_err());
+    }
+
+    #[test]
+    fn test_models() {
+        let account: Account = serde_json::from_str(
+            r#"{
+                "accountId": "a1",
+                "name": "Current account",
+                "balance": 0.1,
+                "currency": "EUR",
+                "identifierScheme": "IBAN",
+                "identifier": "FI0000000000000001",
+                "servicerScheme": "BIC",
+                "servicerIdentifier": "OKOYFIHH"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(Some(eur("0.1")), account.balance);
+        assert_eq!(Currency::eur(), account.currency);
+        // Balance in another currency cannot be sent in the API format
+        let mut sek = account.clone();
+        sek.currency = Currency::new("SEK").unwrap();
+        assert!(serde_json::to_string(&sek).is_err());
+
+        let transaction: Transaction = serde_json::from_str(
+            r#"{
+                "transactionId": "t1",
+                "accountId": "a1",
+                "amount": "-12.50",
+                "currency": "EUR",
+                "creditDebitIndicator": "debit",
+                "accountBalance": "1000.10",
+                "bookingDateTime": "2020-10-01T12:00:00Z",
+                "valueDateTime": "2020-10-01T12:00:00Z"
+            }"#,
+        )
+        .unwrap();
+        let mut sek = transaction.clone();
+        sek.account_balance = Money::parse("1000.10", "SEK").unwrap();
+        assert!(serde_json::to_string(&sek).is_err());
+        assert!(serde_json::to_string(&transaction).is_ok());
+        assert_eq!(eur("-12.50"), transaction.amount);
+        assert_eq!(
+            eur("1012.60"),
+            transaction.account_balance - transaction.amount
+        );
+    }
+}
@@ -0,0 +1,179 @@
+mod common;
+
+#[cfg(test)]
+mod options_tests {
+    use super::common::{config_error, MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::options::{Environment, Options};
+    use reqwest::Client;
+    use std::time::Duration;
+
+    #[test]
+    fn test_environments() {
+        let options = Options::builder().api_key("key").build().unwrap();
+        assert_eq!("https://sandbox.apis.op-palvelut.fi", options.base_url());
+        assert_eq!(
+            "b6910384440ce06f495976f96a162e2ab1bafbb4",
+            options.authorization()
+        );
+
+        let options = Options::builder()
+            .api_key("key")
+            .authorization("token")
+            .environment(Environment::Production)
+            .build()
+            .unwrap();
+        assert_eq!("https://prod.apis.op-palvelut.fi", options.base_url());
+        assert_eq!("token", options.authorization());
+
+        let options = Options::builder()
+            .api_key("key")
+            .base_url("http://localhost:8080/proxy//")
+            .api_version("accounts", "v2")
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(2))
+            .build()
+            .unwrap();
+        assert_eq!("http://localhost:8080/proxy", options.base_url());
+        assert_eq!(Some("v2"), options.api_version("accounts"));
+        assert_eq!(None, options.api_version("mobility"));
+        assert_eq!(Some(Duration::from_secs(10)), options.timeout());
+        assert_eq!(Some(Duration::from_secs(2)), options.connect_timeout());
+    }
+
+    #[test]
+    fn test_validation() {
+        let msg = config_error(Options::builder().build());
+        assert_eq!("API key is not set", msg);
+
+        let msg = config_error(
+            Options::builder()
+                .api_key("key")
+                .environment(Environment::Production)
+                .build(),
+        );
+        assert_eq!(
+            "authorization or authenticator is required for production",
+            msg
+        );
+
+        let msg = config_error(
+            Options::builder()
+                .api_key("key")
+                .base_url("localhost")
+                .build(),
+        );
+        assert!(msg.starts_with("invalid base URL"), "{}", msg);
+
+        let msg = config_error(
+            Options::builder()
+                .api_key("key")
+                .base_url("ftp://localhost")
+                .build(),
+        );
+        assert!(msg.starts_with("base URL must use http"), "{}", msg);
+
+        let msg = config_error(
+            Options::builder()
+                .api_key("key")
+                .timeout(Duration::from_secs(0))
+                .build(),
+        );
+        assert_eq!("timeout must be positive", msg);
+
+        let msg = config_error(
+            Options::builder()
+                .api_key("key")
+                .client(Client::new())
+                .proxy("http://proxy:3128")
+                .build(),
+        );
+        assert!(msg.contains("custom client"), "{}", msg);
+
+        let mut options = Options::new_dev(String::from("key"));
+        let msg = config_error(options.set_connect_timeout(Duration::from_secs(0)));
+        assert_eq!("timeout must be positive", msg);
+        options.set_client(Client::new());
+        let msg = config_error(options.set_connect_timeout(Duration::from_secs(1)));
+        assert!(msg.contains("custom client"), "{}", msg);
+        assert_eq!(None, options.connect_timeout());
+    }
+
+    #[tokio::test]
+    async fn test_request_url_and_user_agent() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+        ]);
+        let options = Options::builder()
+            .api_key("key")
+            .base_url(&format!("{}/", server.url()))
+            .user_agent("my-app/1.0")
+            .build()
+            .unwrap();
+        assert!(Accounts::new(options).accounts().await.is_ok());
+
+        // Options without builder are joined without double slashes as well
+        let mut options = Options::new_dev(String::from("key"));
+        options.set_base_url(format!("{}/", server.url()));
+        assert!(Accounts::new(options).accounts().await.is_ok());
+
+        let requests = server.requests();
+        assert_eq!("/accounts/v3/accounts", requests[0].path);
+        assert_eq!(Some("my-app/1.0"), requests[0].header("user-agent"));
+        assert_eq!("/accounts/v3/accounts", requests[1].path);
+    }
+
+    #[tokio::test]
+    async fn test_api_versions() {
+        let response = MockResponse::json(200, r#"{"accounts":[]}"#);
+        let server = MockServer::start(vec![response; 3]);
+        let mut options = Options::new_dev(String::from("key"));
+        options.set_base_url(server.url().to_string());
+        let default = Accounts::new(options.clone());
+        assert_eq!("v3", default.version());
+        assert!(default.accounts().await.is_ok());
+
+        options.set_api_version("accounts", String::from("v4"));
+        options.set_api_version("mobility", String::from("v1"));
+        let overridden = Accounts::new(options);
+        assert_eq!("v4", overridden.version());
+        assert!(overridden.accounts().await.is_ok());
+        assert!(overridden.with_version("v5").accounts().await.is_ok());
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            vec![
+                "/accounts/v3/accounts",
+                "/accounts/v4/accounts",
+                "/accounts/v5/accounts"
+            ],
+            paths
+        );
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_deprecated_version() {
+        let response = MockResponse::json(200, r#"{"accounts":[]}"#);
+        let server = MockServer::start(vec![response; 2]);
+        let mut options = Options::new_dev(String::from("key"));
+        options.set_base_url(server.url().to_string());
+        assert_eq!("", options.version());
+
+        options.set_version(String::from("v2"));
+        assert_eq!("v2", options.version());
+        assert_eq!(Some("v2"), options.api_version("mobility"));
+        assert!(Accounts::new(options.clone()).accounts().await.is_ok());
+
+        // Per-API version takes precedence
+        options.set_api_version("accounts", String::from("v3"));
+        assert!(Accounts::new(options).accounts().await.is_ok());
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            vec!["/accounts/v2/accounts", "/accounts/v3/accounts"],
+            paths
+        );
+    }
+}
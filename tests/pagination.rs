@@ -0,0 +1,129 @@
+mod common;
+
+#[cfg(test)]
+mod pagination_tests {
+    use super::common::{options, MockResponse, MockServer};
+    use futures::StreamExt;
+    use op_api_sdk::apis::accounts::Accounts;
+
+    fn transaction(id: &str) -> String {
+        format!(
+            r#"{{
+                "transactionId": "{}",
+                "accountId": "a1",
+                "amount": "-12.50",
+                "currency": "EUR",
+                "creditDebitIndicator": "debit",
+                "accountBalance": "100.00",
+                "bookingDateTime": "2020-10-01T12:00:00Z",
+                "valueDateTime": "2020-10-01T12:00:00Z"
+            }}"#,
+            id
+        )
+    }
+
+    fn page(ids: &[&str], next: Option<&str>) -> MockResponse {
+        let transactions: Vec<String> = ids.iter().map(|id| transaction(id)).collect();
+        let links = match next {
+            Some(href) => format!(r#"{{"next":{{"href":"{}"}}}}"#, href),
+            None => String::from("{}"),
+        };
+        MockResponse::json(
+            200,
+            &format!(
+                r#"{{"transactions":[{}],"_links":{}}}"#,
+                transactions.join(","),
+                links
+            ),
+        )
+    }
+
+    fn client(server: &MockServer) -> Accounts {
+        Accounts::new(options(server))
+    }
+
+    const NEXT_2: &str = "/accounts/v3/accounts/a1/transactions?forwardPagingToken=p2";
+    const NEXT_3: &str =
+        "https://sandbox.apis.op-palvelut.fi/accounts/v3/accounts/a1/transactions?forwardPagingToken=p3";
+
+    #[tokio::test]
+    async fn test_collect_all() {
+        let server = MockServer::start(vec![
+            page(&["t1", "t2"], Some(NEXT_2)),
+            page(&[], Some(NEXT_3)),
+            page(&["t3"], None),
+        ]);
+        let transactions = client(&server)
+            .transactions_stream(String::from("a1"), None)
+            .collect_all()
+            .await
+            .unwrap();
+        let ids: Vec<&str> = transactions
+            .iter()
+            .map(|t| t.transaction_id.as_str())
+            .collect();
+        assert_eq!(vec!["t1", "t2", "t3"], ids);
+
+        let requests = server.requests();
+        assert_eq!(3, requests.len());
+        assert_eq!("/accounts/v3/accounts/a1/transactions", requests[0].path);
+        assert!(
+            requests[1].path.ends_with("?forwardPagingToken=p2"),
+            "{}",
+            requests[1].path
+        );
+        assert!(
+            requests[2].path.ends_with("?forwardPagingToken=p3"),
+            "{}",
+            requests[2].path
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_items() {
+        let server = MockServer::start(vec![
+            page(&["t1", "t2"], Some(NEXT_2)),
+            page(&["t3", "t4"], Some(NEXT_3)),
+            page(&["t5"], None),
+        ]);
+        let transactions = client(&server)
+            .transactions_stream(String::from("a1"), None)
+            .max_items(3)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(3, transactions.len());
+        assert_eq!("t3", transactions[2].transaction_id);
+        // Third page is never needed
+        assert_eq!(2, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_error_ends_stream() {
+        let server = MockServer::start(vec![
+            page(&["t1"], Some(NEXT_2)),
+            MockResponse::json(500, r#"{"errors":[]}"#),
+        ]);
+        let mut stream = client(&server).transactions_stream(String::from("a1"), None);
+        assert_eq!("t1", stream.next().await.unwrap().unwrap().transaction_id);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(Some(500), err.status().map(|s| s.as_u16()));
+        assert!(stream.next().await.is_none());
+        assert_eq!(2, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_token_stops() {
+        let server = MockServer::start(vec![
+            page(&["t1"], Some(NEXT_2)),
+            page(&["t2"], Some(NEXT_2)),
+        ]);
+        let transactions = client(&server)
+            .transactions_stream(String::from("a1"), None)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(2, transactions.len());
+        assert_eq!(2, server.requests().len());
+    }
+}
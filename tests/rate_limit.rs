@@ -0,0 +1,46 @@
+mod common;
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::common::{MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::rate_limit::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2, Duration::from_millis(400));
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.clone().acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // Bucket is empty, third request must wait for a refill
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[tokio::test]
+    async fn test_shared_between_clients() {
+        let response = MockResponse::json(200, r#"{"accounts":[]}"#);
+        let server = MockServer::start(vec![response; 4]);
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(server.url().to_string());
+        options.set_rate_limiter(RateLimiter::new(2, Duration::from_millis(500)));
+        let first = Accounts::new(options.clone());
+        let second = Accounts::new(options);
+
+        let start = Instant::now();
+        let results = tokio::join!(
+            first.accounts(),
+            second.accounts(),
+            first.accounts(),
+            second.accounts()
+        );
+        assert!(results.0.is_ok() && results.1.is_ok());
+        assert!(results.2.is_ok() && results.3.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(450));
+        assert_eq!(4, server.requests().len());
+    }
+}
@@ -0,0 +1,98 @@
+mod common;
+
+#[cfg(test)]
+mod redact_tests {
+    use super::common::{capture_logs, MockResponse, MockServer};
+    use op_api_sdk::auth::{Authenticator, OAuthConfig, Token};
+    use op_api_sdk::authorization::Pkce;
+    use op_api_sdk::config::Config;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::tls::TlsConfig;
+    use op_api_sdk::transport::HttpResponse;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use zeroize::Zeroizing;
+
+    const SECRET: &str = "very-secret-value";
+
+    fn assert_redacted<T: std::fmt::Debug>(value: &T) {
+        let debug = format!("{:?}", value);
+        assert!(!debug.contains(SECRET), "secret in {}", debug);
+        assert!(
+            debug.contains("[REDACTED]"),
+            "nothing redacted in {}",
+            debug
+        );
+    }
+
+    #[test]
+    fn test_debug_output() {
+        let options = Options::new(String::from(SECRET), String::from(SECRET));
+        assert_redacted(&options);
+        assert_redacted(&Options::new_dev(String::from(SECRET)));
+
+        let mut config = OAuthConfig::new("https://auth.example.com/token", "client");
+        config.set_client_secret(SECRET);
+        assert_redacted(&config);
+
+        let mut token = Token::new(SECRET);
+        token.refresh_token = Some(String::from(SECRET));
+        assert_redacted(&token);
+        assert_redacted(&Authenticator::with_token(config, token));
+
+        let mut tls = TlsConfig::new();
+        tls.set_identity_pkcs12(b"der", SECRET);
+        assert_redacted(&tls);
+
+        let config = Config {
+            api_key: Some(Zeroizing::new(String::from(SECRET))),
+            authorization: Some(Zeroizing::new(String::from(SECRET))),
+            ..Config::default()
+        };
+        assert_redacted(&config);
+
+        assert_redacted(&Pkce::from_verifier(SECRET));
+
+        // Bodies may contain tokens or account data, only the length is shown
+        let response = HttpResponse::new(StatusCode::OK, HeaderMap::new(), SECRET.into());
+        let debug = format!("{:?}", response);
+        assert!(!debug.contains(SECRET), "secret in {}", debug);
+        assert!(
+            debug.contains(&format!("body: {}", SECRET.len())),
+            "{}",
+            debug
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_logging() {
+        let logger = capture_logs();
+
+        let server = MockServer::start(vec![MockResponse::json(200, r#"{"accounts":[]}"#)]);
+        let options = Options::builder()
+            .api_key(SECRET)
+            .authorization(SECRET)
+            .base_url(server.url())
+            .build()
+            .unwrap();
+        op_api_sdk::accounts::Accounts::new(options)
+            .accounts()
+            .await
+            .unwrap();
+        assert_eq!(Some(SECRET), server.requests()[0].header("x-api-key"));
+
+        let messages = logger.all_messages();
+        let sent: Vec<&String> = messages
+            .iter()
+            .filter(|m| m.starts_with("Sending request"))
+            .collect();
+        assert_eq!(1, sent.len());
+        assert!(sent[0].contains("\"x-api-key\": [REDACTED]"), "{}", sent[0]);
+        assert!(
+            sent[0].contains("\"authorization\": [REDACTED]"),
+            "{}",
+            sent[0]
+        );
+        assert!(messages.iter().all(|m| !m.contains(SECRET)));
+    }
+}
@@ -0,0 +1,132 @@
+mod common;
+
+#[cfg(test)]
+mod request_id_tests {
+    use super::common::{options, MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::auth::{Authenticator, OAuthConfig};
+    use op_api_sdk::options::Options;
+    use op_api_sdk::retry::RetryPolicy;
+    use op_api_sdk::Error;
+    use std::time::Duration;
+
+    fn is_uuid(id: &str) -> bool {
+        let parts: Vec<usize> = id.split('-').map(|p| p.len()).collect();
+        parts == vec![8, 4, 4, 4, 12] && id.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+    }
+
+    #[tokio::test]
+    async fn test_generated_request_id() {
+        let response = MockResponse::json(200, r#"{"accounts":[]}"#);
+        let server = MockServer::start(vec![
+            response.clone(),
+            response.header("x-request-id", "from-api"),
+        ]);
+        let client = Accounts::new(options(&server));
+        let first = client.accounts().await.unwrap();
+        let second = client.accounts().await.unwrap();
+
+        let requests = server.requests();
+        let sent = requests[0].header("x-request-id").unwrap();
+        assert!(is_uuid(sent), "{}", sent);
+        assert_ne!(requests[1].header("x-request-id"), Some(sent));
+        assert_eq!(None, requests[0].header("x-session-id"));
+        assert_eq!(Some(sent), first.request_id());
+        assert_eq!(Some("from-api"), second.request_id());
+        assert!(second.into_inner().accounts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_caller_supplied_ids() {
+        let server = MockServer::start(vec![
+            MockResponse::json(503, "unavailable"),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+        ]);
+        let mut retry_policy = RetryPolicy::new();
+        retry_policy.set_initial_backoff(Duration::from_millis(1));
+        let options = Options::builder()
+            .api_key("test-key")
+            .base_url(server.url())
+            .retry_policy(retry_policy)
+            .session_id("session-1")
+            .build()
+            .unwrap();
+        let resp = Accounts::new(options)
+            .with_request_id("request-1")
+            .accounts()
+            .await
+            .unwrap();
+        assert_eq!(Some("request-1"), resp.request_id());
+
+        // Retries are sent with the same ids
+        for request in server.requests().iter() {
+            assert_eq!(Some("request-1"), request.header("x-request-id"));
+            assert_eq!(Some("session-1"), request.header("x-session-id"));
+        }
+        assert_eq!(2, server.requests().len());
+
+        let result = Options::builder()
+            .api_key("test-key")
+            .session_id("invalid\nid")
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_request_id_in_errors() {
+        let server = MockServer::start(vec![
+            MockResponse::json(500, "failure").header("x-request-id", "from-api"),
+            MockResponse::json(500, "failure"),
+            MockResponse::json(200, "not json"),
+        ]);
+        let client = Accounts::new(options(&server)).with_request_id("request-1");
+
+        let e = client.accounts().await.unwrap_err();
+        assert_eq!(Some("from-api"), e.request_id());
+        assert_eq!(
+            "HTTP 500 Internal Server Error: failure [request id from-api]",
+            e.to_string()
+        );
+        let e = client.accounts().await.unwrap_err();
+        assert_eq!(Some("request-1"), e.request_id());
+        let e = client.accounts().await.unwrap_err();
+        assert!(matches!(e, Error::Deserialize { .. }));
+        assert_eq!(Some("request-1"), e.request_id());
+
+        let mut options = options(&server);
+        options.set_base_url(String::from("http://127.0.0.1:1"));
+        let e = Accounts::new(options)
+            .with_request_id("request-2")
+            .accounts()
+            .await
+            .unwrap_err();
+        assert!(matches!(e, Error::Transport(_)), "{:?}", e);
+        assert_eq!(Some("request-2"), e.request_id());
+        assert!(e.to_string().ends_with("[request id request-2]"));
+
+        let e = Options::builder().build().unwrap_err();
+        assert_eq!(None, e.request_id());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_in_auth_errors() {
+        let server = MockServer::start(vec![MockResponse::json(
+            400,
+            r#"{"error":"invalid_client"}"#,
+        )]);
+        let config = OAuthConfig::new(&format!("{}/oauth/token", server.url()), "client");
+        let mut options = options(&server);
+        options.set_authenticator(Authenticator::client_credentials(config));
+        let e = Accounts::new(options)
+            .with_request_id("request-1")
+            .accounts()
+            .await
+            .unwrap_err();
+        assert!(matches!(e, Error::Auth { .. }), "{:?}", e);
+        assert_eq!(Some("request-1"), e.request_id());
+        assert_eq!(
+            "Authentication failed: invalid_client [request id request-1]",
+            e.to_string()
+        );
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod request_object_tests {
+    use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+    use op_api_sdk::auth::OAuthConfig;
+    use op_api_sdk::authorization::{AuthorizationFlow, Pkce};
+    use op_api_sdk::request_object::*;
+    use op_api_sdk::Error;
+    use reqwest::Url;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    const PRIVATE_KEY: &[u8] = include_bytes!("fixtures/jwt/private.pem");
+    const PUBLIC_KEY: &[u8] = include_bytes!("fixtures/jwt/public.pem");
+    const AUDIENCE: &str = "https://mtls-apis.psd2-sandbox.op-palvelut.fi";
+
+    fn request_object() -> RequestObject {
+        let mut request = RequestObject::new(
+            "tpp-client",
+            AUDIENCE,
+            "https://my.app/callback",
+            "state123",
+            "jti123",
+        );
+        request.iat = 1_600_000_000;
+        request.exp = 1_600_000_300;
+        request.scope = Some(String::from("openid accounts"));
+        request.set_authorization_id("auth-1");
+        request
+    }
+
+    fn decode_claims(jwt: &str, algorithm: Algorithm) -> Value {
+        let mut validation = Validation::new(algorithm);
+        validation.validate_exp = false;
+        validation.set_audience(&[AUDIENCE]);
+        decode::<Value>(
+            jwt,
+            &DecodingKey::from_rsa_pem(PUBLIC_KEY).unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims
+    }
+
+    #[test]
+    fn test_sign_rs256() {
+        let mut signer =
+            RequestObjectSigner::from_rsa_pem(PRIVATE_KEY, SigningAlgorithm::RS256, AUDIENCE)
+                .unwrap();
+        signer.set_key_id("key-1");
+        let jwt = signer.sign(&request_object()).unwrap();
+        assert_eq!(EXPECTED_RS256, jwt);
+    }
+
+    #[test]
+    fn test_sign_ps256() {
+        let signer =
+            RequestObjectSigner::from_rsa_pem(PRIVATE_KEY, SigningAlgorithm::PS256, AUDIENCE)
+                .unwrap();
+        let jwt = signer.sign(&request_object()).unwrap();
+        let claims = decode_claims(&jwt, Algorithm::PS256);
+        assert_eq!("tpp-client", claims["iss"]);
+        assert_eq!("state123", claims["state"]);
+        assert_eq!(
+            json!({ "value": "auth-1", "essential": true }),
+            claims["claims"]["id_token"]["authorizationId"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let result =
+            RequestObjectSigner::from_rsa_pem(b"not a key", SigningAlgorithm::RS256, AUDIENCE);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let mut config = OAuthConfig::new("https://auth.example.com/oauth/token", "tpp-client");
+        config.set_scope("openid accounts");
+        let mut flow = AuthorizationFlow::new(
+            "https://auth.example.com/oauth/authorize",
+            config,
+            "https://my.app/callback",
+        );
+        flow.set_request_signer(
+            RequestObjectSigner::from_rsa_pem(PRIVATE_KEY, SigningAlgorithm::RS256, AUDIENCE)
+                .unwrap(),
+        );
+        flow.set_authorization_id("auth-1");
+
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        let request = flow.start_with("state123", &pkce).unwrap();
+        let url = Url::parse(&request.url).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!("tpp-client", params["client_id"]);
+
+        let claims = decode_claims(&params["request"], Algorithm::RS256);
+        assert_eq!("code", claims["response_type"]);
+        assert_eq!("https://my.app/callback", claims["redirect_uri"]);
+        assert_eq!("openid accounts", claims["scope"]);
+        assert_eq!(pkce.challenge, claims["code_challenge"]);
+        assert_eq!("S256", claims["code_challenge_method"]);
+        assert_eq!(
+            "auth-1",
+            claims["claims"]["userinfo"]["authorizationId"]["value"]
+        );
+        assert_eq!(
+            300,
+            claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap()
+        );
+    }
+
+    // Signed independently with `openssl dgst -sha256 -sign private.pem`
+    const EXPECTED_RS256: &str = concat!(
+        "eyJ0eXAiOiJKV1QiLCJhbGciOiJSUzI1NiIsImtpZCI6ImtleS0xIn0.",
+        "eyJpc3MiOiJ0cHAtY2xpZW50IiwiYXVkIjoiaHR0cHM6Ly9tdGxzLWFwaXMucHNk",
+        "Mi1zYW5kYm94Lm9wLXBhbHZlbHV0LmZpIiwicmVzcG9uc2VfdHlwZSI6ImNvZGUi",
+        "LCJjbGllbnRfaWQiOiJ0cHAtY2xpZW50IiwicmVkaXJlY3RfdXJpIjoiaHR0cHM6",
+        "Ly9teS5hcHAvY2FsbGJhY2siLCJzY29wZSI6Im9wZW5pZCBhY2NvdW50cyIsInN0",
+        "YXRlIjoic3RhdGUxMjMiLCJpYXQiOjE2MDAwMDAwMDAsImV4cCI6MTYwMDAwMDMw",
+        "MCwianRpIjoianRpMTIzIiwiY2xhaW1zIjp7ImlkX3Rva2VuIjp7ImF1dGhvcml6",
+        "YXRpb25JZCI6eyJlc3NlbnRpYWwiOnRydWUsInZhbHVlIjoiYXV0aC0xIn19LCJ1",
+        "c2VyaW5mbyI6eyJhdXRob3JpemF0aW9uSWQiOnsiZXNzZW50aWFsIjp0cnVlLCJ2",
+        "YWx1ZSI6ImF1dGgtMSJ9fX19",
+        ".C0uAV1d9mHrsKtNJ-VCT-VMN9mvtE4GNfWEfDuZYETH2faI31b7ahjJ2Fa9lO1MEo30slsXcN8CyoOm4S-tOqcSyl4P0ETjgWRAja2IzR4QKKQPUUxv4z4FlWcQGDI37h0nRs7Ca62fiJ97B_6QRhTKO2T2u7kBze3CGfPXaUa5PGWA1TNlLQSTPULXC2TaxjUlnHVlUeZKCJN30aU1F-QCTKsuMnweZuLWrNB8txydtOIBc2KZBahlTtFf_YTmbWXCELaHnrYbAFv--mFpL-DkAKAqRyKoi6g29gyru2Pj63Jk-2LGzfyI643HJSYq3DbSEtXRfOzMQ8Qdyhbd98w"
+    );
+}
@@ -0,0 +1,149 @@
+mod common;
+
+#[cfg(test)]
+mod requests_tests {
+    use super::common::{options, MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::error::ApiErrors;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::requests::Requests;
+    use op_api_sdk::Error;
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::Client;
+    use serde::Serialize;
+
+    fn client(server: &MockServer) -> Accounts {
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(server.url().to_string());
+        Accounts::new(options)
+    }
+
+    #[tokio::test]
+    async fn test_api_error() {
+        let body =
+            r#"{"errors":[{"id":"1","level":"error","type":"auth","message":"Unauthorized"}]}"#;
+        let server = MockServer::start(vec![
+            MockResponse::json(401, body).header("x-request-id", "abc-123")
+        ]);
+        let resp = client(&server).accounts().await;
+        match resp {
+            Err(Error::Api {
+                status,
+                headers,
+                body: raw,
+                errors,
+            }) => {
+                assert_eq!(401, status.as_u16());
+                assert_eq!("abc-123", headers["x-request-id"]);
+                assert_eq!(body, raw);
+                let errors = errors.expect("ApiErrors should be parsed");
+                assert_eq!("Unauthorized", errors.errors[0].message);
+                assert_eq!(body, serde_json::to_string(&errors).unwrap());
+                assert_eq!(errors, serde_json::from_str::<ApiErrors>(body).unwrap());
+            }
+            other => panic!("Expected API error, got {:?}", other),
+        }
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!("GET", requests[0].method);
+        assert_eq!("/accounts/v3/accounts", requests[0].path);
+        assert_eq!(Some("test-key"), requests[0].header("x-api-key"));
+    }
+
+    #[tokio::test]
+    async fn test_non_json_error() {
+        let server = MockServer::start(vec![
+            MockResponse::new(502, "<html>Bad Gateway</html>").header("Retry-After", "30")
+        ]);
+        let resp = Accounts::new(options(&server)).accounts().await;
+        match resp {
+            Err(e @ Error::Api { .. }) => {
+                assert_eq!(Some(502), e.status().map(|s| s.as_u16()));
+                assert_eq!(Some("<html>Bad Gateway</html>"), e.body());
+                assert_eq!("30", e.headers().unwrap()["retry-after"]);
+                assert!(matches!(e, Error::Api { errors: None, .. }));
+            }
+            other => panic!("Expected API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_success_statuses() {
+        let server = MockServer::start(vec![MockResponse::json(203, r#"{"accounts":[]}"#)]);
+        let resp = client(&server).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+
+        let server = MockServer::start(vec![MockResponse::json(200, "not json")]);
+        match client(&server).accounts().await {
+            Err(e @ Error::Deserialize { .. }) => assert_eq!(Some("not json"), e.body()),
+            other => panic!("Expected deserialize error, got {:?}", other),
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Payload {
+        amount: String,
+    }
+
+    #[tokio::test]
+    async fn test_body_methods() {
+        let server = MockServer::start(vec![
+            MockResponse::json(201, r#"{"ok":true}"#),
+            MockResponse::new(204, ""),
+            MockResponse::new(200, ""),
+            MockResponse::new(204, ""),
+        ]);
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(server.url().to_string());
+        let payload = Payload {
+            amount: String::from("12.50"),
+        };
+
+        let resp = Requests::post(&options, "/payments", None::<()>, &payload).await;
+        let value: serde_json::Value = Requests::json(resp.unwrap()).unwrap();
+        assert_eq!(true, value["ok"]);
+        let resp = Requests::put(&options, "/payments/1", Some(&[("a", "b")]), &payload).await;
+        assert_eq!(204, resp.unwrap().status.as_u16());
+        let resp = Requests::patch(&options, "/payments/1", None::<()>, &payload).await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        let resp = Requests::delete(&options, "/payments/1", None::<()>).await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+
+        let requests = server.requests();
+        let methods: Vec<&str> = requests.iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(vec!["POST", "PUT", "PATCH", "DELETE"], methods);
+        assert_eq!("/payments/1?a=b", requests[1].path);
+        for request in requests.iter().take(3) {
+            assert_eq!(r#"{"amount":"12.50"}"#, request.body);
+            assert_eq!(Some("application/json"), request.header("content-type"));
+            assert_eq!(Some("test-key"), request.header("x-api-key"));
+        }
+        assert_eq!("", requests[3].body);
+    }
+
+    #[tokio::test]
+    async fn test_custom_client() {
+        let server = MockServer::start_keep_alive(vec![
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+        ]);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-custom", HeaderValue::from_static("custom"));
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(server.url().to_string());
+        options.set_client(Client::builder().default_headers(headers).build().unwrap());
+
+        let first = Accounts::new(options.clone());
+        let second = Accounts::new(options);
+        assert!(first.accounts().await.is_ok());
+        assert!(second.accounts().await.is_ok());
+
+        for request in server.requests() {
+            assert_eq!(Some("custom"), request.header("x-custom"));
+        }
+        // Clients created from the same Options share the connection pool
+        assert_eq!(2, server.requests().len());
+        assert_eq!(1, server.connections());
+    }
+}
@@ -0,0 +1,148 @@
+mod common;
+
+#[cfg(test)]
+mod retry_tests {
+    use super::common::{self, MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::requests::Requests;
+    use op_api_sdk::retry::RetryPolicy;
+    use op_api_sdk::Error;
+    use std::time::{Duration, Instant};
+
+    fn options(server: &MockServer) -> Options {
+        let mut policy = RetryPolicy::new();
+        policy.set_initial_backoff(Duration::from_millis(10));
+        let mut options = common::options(server);
+        options.set_retry_policy(policy);
+        options
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, "unavailable"),
+            MockResponse::new(502, "bad gateway"),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+        ]);
+        let resp = Accounts::new(options(&server)).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        assert_eq!(3, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, "unavailable"),
+            MockResponse::new(503, "unavailable"),
+            MockResponse::new(503, "still unavailable"),
+        ]);
+        let resp = Accounts::new(options(&server)).accounts().await;
+        match resp {
+            Err(e @ Error::Api { .. }) => assert_eq!(Some("still unavailable"), e.body()),
+            other => panic!("Expected API error, got {:?}", other),
+        }
+        assert_eq!(3, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_retry_after() {
+        let server = MockServer::start(vec![
+            MockResponse::new(429, "slow down").header("Retry-After", "1"),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+        ]);
+        let start = Instant::now();
+        let resp = Accounts::new(options(&server)).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_above_max_backoff() {
+        let server = MockServer::start(vec![
+            MockResponse::new(429, "slow down").header("Retry-After", "86400"),
+            MockResponse::new(503, "unavailable")
+                .header("Retry-After", "Fri, 31 Dec 2100 23:59:59 GMT"),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+        ]);
+        let client = Accounts::new(options(&server));
+        let start = Instant::now();
+        for status in [429, 503].iter() {
+            let resp = client.accounts().await;
+            assert_eq!(
+                Some(*status),
+                resp.err().and_then(|e| e.status()).map(|s| s.as_u16())
+            );
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(2, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_retry_timeout() {
+        let slow = MockResponse::json(200, r#"{"accounts":[]}"#).delay(Duration::from_millis(300));
+        let server = MockServer::start(vec![
+            slow.clone(),
+            MockResponse::json(200, r#"{"accounts":[]}"#),
+            slow,
+        ]);
+        // Server handles one connection at a time, so retry after it is done
+        // with the timed out one
+        let mut policy = RetryPolicy::new();
+        policy.set_initial_backoff(Duration::from_millis(500));
+        policy.set_jitter(false);
+        let mut options = options(&server);
+        options.set_retry_policy(policy);
+        options.set_timeout(Duration::from_millis(100));
+        let resp = Accounts::new(options.clone()).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        assert_eq!(2, server.requests().len());
+
+        let mut policy = RetryPolicy::new();
+        policy.set_retry_transport_errors(false);
+        options.set_retry_policy(policy);
+        let resp = Accounts::new(options).accounts().await;
+        assert!(matches!(resp, Err(Error::Timeout(_))), "{:?}", resp);
+        assert_eq!(3, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_no_retry() {
+        let server = MockServer::start(vec![
+            MockResponse::new(400, "bad request"),
+            MockResponse::new(503, "unavailable"),
+            MockResponse::new(503, "unavailable"),
+        ]);
+        let options = options(&server);
+        let resp = Requests::get(&options, "/accounts", None::<()>).await;
+        assert_eq!(
+            Some(400),
+            resp.err().and_then(|e| e.status()).map(|s| s.as_u16())
+        );
+
+        // POST is not idempotent and must not be retried by default
+        let resp = Requests::post(&options, "/payments", None::<()>, &()).await;
+        assert_eq!(
+            Some(503),
+            resp.err().and_then(|e| e.status()).map(|s| s.as_u16())
+        );
+        assert_eq!(2, server.requests().len());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut policy = RetryPolicy::new();
+        policy.set_initial_backoff(Duration::from_millis(100));
+        policy.set_max_backoff(Duration::from_millis(300));
+        let error = Error::Config(String::from("not used for delay"));
+        for _ in 0..10 {
+            let delay = policy.delay(1, &error);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+        }
+        policy.set_jitter(false);
+        assert_eq!(Duration::from_millis(100), policy.delay(1, &error));
+        assert_eq!(Duration::from_millis(200), policy.delay(2, &error));
+        assert_eq!(Duration::from_millis(300), policy.delay(3, &error));
+        assert_eq!(Duration::from_millis(300), policy.delay(40, &error));
+    }
+}
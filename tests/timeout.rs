@@ -0,0 +1,49 @@
+mod common;
+
+#[cfg(test)]
+mod timeout_tests {
+    use super::common::{options, MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::retry::RetryPolicy;
+    use op_api_sdk::Error;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let slow = MockResponse::json(200, r#"{"accounts":[]}"#).delay(Duration::from_millis(500));
+        let server = MockServer::start(vec![slow.clone(), slow]);
+        let mut options = options(&server);
+        options.set_timeout(Duration::from_millis(100));
+        let client = Accounts::new(options);
+
+        match client.accounts().await {
+            Err(e @ Error::Timeout(_)) => assert_eq!(None, e.status()),
+            other => panic!("Expected timeout error, got {:?}", other),
+        }
+
+        // Per-call override allows slower responses
+        let resp = client.with_timeout(Duration::from_secs(5)).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+    }
+
+    // Depends on the network of the host, run with --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_connect_timeout() {
+        // Non-routable address, so the connection is never established
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(String::from("http://10.255.255.1:81"));
+        options.set_retry_policy(RetryPolicy::none());
+        options
+            .set_connect_timeout(Duration::from_millis(200))
+            .unwrap();
+        assert_eq!(Some(Duration::from_millis(200)), options.connect_timeout());
+        assert_eq!(None, options.timeout());
+
+        let start = Instant::now();
+        let resp = Accounts::new(options).accounts().await;
+        assert!(matches!(resp, Err(Error::Timeout(_))), "{:?}", resp);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+#[cfg(test)]
+mod tls_tests {
+    use super::common::{builder, MockResponse, MockServer};
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::tls::TlsConfig;
+    use op_api_sdk::Error;
+    use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslVerifyMode};
+    use std::time::Duration;
+
+    // Self-signed test CA with server and client certificates signed by it
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls");
+    const ACCOUNTS: &str = r#"{"accounts":[]}"#;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/{}", FIXTURES, name)
+    }
+
+    /// Server requiring client certificate signed by the test CA.
+    fn server(responses: usize) -> MockServer {
+        let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls()).unwrap();
+        acceptor
+            .set_private_key_file(fixture("server.key"), SslFiletype::PEM)
+            .unwrap();
+        acceptor
+            .set_certificate_chain_file(fixture("server.pem"))
+            .unwrap();
+        acceptor.set_ca_file(fixture("ca.pem")).unwrap();
+        acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        let response = MockResponse::json(200, ACCOUNTS);
+        MockServer::start_tls(vec![response; responses], acceptor.build())
+    }
+
+    fn client(server: &MockServer, tls: TlsConfig) -> Accounts {
+        Accounts::new(builder(server).tls(tls).build().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_pkcs12_identity() {
+        let server = server(1);
+        let mut tls = TlsConfig::from_pkcs12_file(fixture("client.p12"), "secret").unwrap();
+        tls.add_root_certificate_file(fixture("ca.pem")).unwrap();
+        let resp = client(&server, tls).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        assert_eq!(1, server.requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_pem_identity() {
+        let server = server(2);
+        let mut tls =
+            TlsConfig::from_pem_files(fixture("client.pem"), fixture("client.key")).unwrap();
+        tls.add_root_certificate_file(fixture("ca.pem")).unwrap();
+        let client = client(&server, tls);
+        assert!(client.accounts().await.is_ok());
+        // Same client keeps using the identity
+        assert!(client.accounts().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_keeps_identity() {
+        let server = server(1);
+        let mut tls = TlsConfig::from_pkcs12_file(fixture("client.p12"), "secret").unwrap();
+        tls.add_root_certificate_file(fixture("ca.pem")).unwrap();
+        let mut options = builder(&server)
+            .user_agent("my-app/1.0")
+            .tls(tls)
+            .build()
+            .unwrap();
+        options.set_connect_timeout(Duration::from_secs(5)).unwrap();
+        let resp = Accounts::new(options).accounts().await;
+        assert!(resp.is_ok(), "{:?}", resp.err());
+        assert_eq!(
+            Some("my-app/1.0"),
+            server.requests()[0].header("user-agent")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_identity() {
+        let server = server(1);
+        let mut tls = TlsConfig::new();
+        tls.add_root_certificate_file(fixture("ca.pem")).unwrap();
+        let resp = client(&server, tls).accounts().await;
+        assert!(matches!(resp, Err(Error::Transport(_))), "{:?}", resp);
+
+        // Server certificate is not trusted without the test CA
+        let tls = TlsConfig::from_pkcs12_file(fixture("client.p12"), "secret").unwrap();
+        let resp = client(&server, tls).accounts().await;
+        assert!(matches!(resp, Err(Error::Transport(_))), "{:?}", resp);
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_identity() {
+        let mut tls = TlsConfig::from_pkcs12_file(fixture("client.p12"), "wrong").unwrap();
+        let result = Options::builder().api_key("key").tls(tls.clone()).build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        tls.set_identity_pem(b"not a certificate", b"not a key");
+        let result = Options::builder().api_key("key").tls(tls).build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let result = TlsConfig::new().add_root_certificate_pem(b"garbage");
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+}
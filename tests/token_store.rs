@@ -0,0 +1,113 @@
+mod common;
+
+#[cfg(test)]
+mod token_store_tests {
+    use super::common::{MockResponse, MockServer};
+    use chrono::{Duration, TimeZone, Utc};
+    use op_api_sdk::auth::{Authenticator, OAuthConfig, Token};
+    use op_api_sdk::token_store::*;
+    use op_api_sdk::Error;
+    use reqwest::Client;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn token() -> Token {
+        let mut token = Token::new("access");
+        token.refresh_token = Some(String::from("refresh"));
+        token.expires_at = Some(Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap());
+        token
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("op-api-sdk-{}-{}", std::process::id(), name))
+    }
+
+    fn assert_store(store: &dyn TokenStore) {
+        assert_eq!(None, store.load("user-1").unwrap());
+        store.save("user-1", &token()).unwrap();
+        store.save("../user/2", &Token::new("other")).unwrap();
+        assert_eq!(Some(token()), store.load("user-1").unwrap());
+        assert_eq!(Some(Token::new("other")), store.load("../user/2").unwrap());
+
+        store.remove("user-1").unwrap();
+        store.remove("user-1").unwrap();
+        assert_eq!(None, store.load("user-1").unwrap());
+    }
+
+    #[test]
+    fn test_memory_store() {
+        assert_store(&MemoryTokenStore::new());
+    }
+
+    #[test]
+    fn test_file_store() {
+        let dir = temp_dir("file-store");
+        let store = FileTokenStore::new(&dir).unwrap();
+        assert_store(&store);
+
+        // Tokens survive creating a new store for the same directory
+        store.save("user-1", &token()).unwrap();
+        let reopened = FileTokenStore::new(&dir).unwrap();
+        assert_eq!(Some(token()), reopened.load("user-1").unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_file_store() {
+        let dir = temp_dir("encrypted-store");
+        let store = FileTokenStore::new(&dir)
+            .unwrap()
+            .with_encryption_key([7; 32]);
+        assert_store(&store);
+
+        store.save("user-1", &token()).unwrap();
+        for entry in fs::read_dir(&dir).unwrap() {
+            let content = fs::read(entry.unwrap().path()).unwrap();
+            assert!(!String::from_utf8_lossy(&content).contains("refresh"));
+        }
+
+        let wrong_key = FileTokenStore::new(&dir)
+            .unwrap()
+            .with_encryption_key([8; 32]);
+        match wrong_key.load("user-1") {
+            Err(Error::TokenStore(msg)) => assert_eq!("failed to decrypt token", msg),
+            other => panic!("Expected token store error, got {:?}", other),
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_authenticator_store() {
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"access_token":"new","refresh_token":"rotated","expires_in":3600}"#,
+        )]);
+        let config = OAuthConfig::new(&format!("{}/oauth/token", server.url()), "client");
+        let store: Arc<dyn TokenStore> = Arc::new(MemoryTokenStore::new());
+
+        match Authenticator::from_store(config.clone(), store.clone(), "consent-1") {
+            Err(Error::Auth { error, .. }) => assert_eq!("token_not_found", error),
+            _ => panic!("Expected auth error"),
+        }
+
+        // Token given to the authenticator is saved to the store
+        let mut expired = token();
+        expired.expires_at = Some(Utc::now() - Duration::seconds(1));
+        Authenticator::with_token(config.clone(), expired.clone())
+            .with_store(store.clone(), "consent-1")
+            .await
+            .unwrap();
+        assert_eq!(Some(expired), store.load("consent-1").unwrap());
+
+        // Refreshed token is saved back to the store
+        let authenticator = Authenticator::from_store(config, store.clone(), "consent-1").unwrap();
+        let access_token = authenticator.access_token(&Client::new()).await.unwrap();
+        assert_eq!("new", access_token);
+        let saved = store.load("consent-1").unwrap().unwrap();
+        assert_eq!("new", saved.access_token);
+        assert_eq!(Some(String::from("rotated")), saved.refresh_token);
+    }
+}
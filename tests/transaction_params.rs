@@ -0,0 +1,89 @@
+mod common;
+
+#[cfg(test)]
+mod transaction_params_tests {
+    use super::common::{MockResponse, MockServer};
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+    use op_api_sdk::apis::accounts::{Accounts, TransactionParams};
+    use op_api_sdk::options::Options;
+    use op_api_sdk::Error;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn query(params: &TransactionParams) -> String {
+        serde_urlencoded::to_string(params).unwrap()
+    }
+
+    #[test]
+    fn test_query_string() {
+        let params = TransactionParams::builder()
+            .from(date(2020, 10, 1))
+            .to(date(2020, 10, 31).and_hms_opt(23, 59, 59).unwrap())
+            .page_size(50)
+            .forward_paging_token("abc")
+            .build()
+            .unwrap();
+        assert_eq!(
+            "fromBookingDateTime=2020-10-01T00%3A00%3A00\
+             &toBookingDateTime=2020-10-31T23%3A59%3A59\
+             &pageSize=50&forwardPagingToken=abc",
+            query(&params)
+        );
+
+        assert_eq!("", query(&TransactionParams::default()));
+        let params = TransactionParams::builder().page_size(5).build().unwrap();
+        assert_eq!("pageSize=5", query(&params));
+    }
+
+    #[test]
+    fn test_timezones_are_not_sent() {
+        let helsinki = FixedOffset::east_opt(3 * 3600).unwrap();
+        let from = helsinki.with_ymd_and_hms(2020, 10, 1, 8, 30, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2020, 10, 2, 12, 0, 0).unwrap();
+        let params = TransactionParams::builder()
+            .from(from)
+            .to(to)
+            .build()
+            .unwrap();
+        assert_eq!(
+            "fromBookingDateTime=2020-10-01T08%3A30%3A00&toBookingDateTime=2020-10-02T12%3A00%3A00",
+            query(&params)
+        );
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let result = TransactionParams::builder()
+            .from(date(2020, 10, 2))
+            .to(date(2020, 10, 1))
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+        let result = TransactionParams::builder().page_size(0).build();
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_request_query() {
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"transactions":[],"_links":{}}"#,
+        )]);
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_base_url(server.url().to_string());
+        let params = TransactionParams::builder()
+            .from(date(2020, 1, 1))
+            .page_size(10)
+            .build()
+            .unwrap();
+        Accounts::new(options)
+            .transactions(String::from("a1"), Some(params))
+            .await
+            .unwrap();
+        assert_eq!(
+            "/accounts/v3/accounts/a1/transactions?fromBookingDateTime=2020-01-01T00%3A00%3A00&pageSize=10",
+            server.requests()[0].path
+        );
+    }
+}
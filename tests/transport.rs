@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod transport_tests {
+    use async_trait::async_trait;
+    use op_api_sdk::apis::accounts::Accounts;
+    use op_api_sdk::auth::{Authenticator, OAuthConfig};
+    use op_api_sdk::error::TransportError;
+    use op_api_sdk::options::Options;
+    use op_api_sdk::rate_limit::RateLimiter;
+    use op_api_sdk::retry::RetryPolicy;
+    use op_api_sdk::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use op_api_sdk::{Error, Result};
+    use reqwest::header::HeaderMap;
+    use reqwest::{Client, StatusCode};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// In-process transport returning canned responses.
+    #[derive(Default)]
+    struct StubTransport {
+        responses: Mutex<Vec<Result<HttpResponse>>>,
+        requests: Mutex<Vec<HttpRequest>>,
+        sleeps: Mutex<Vec<Duration>>,
+    }
+
+    impl StubTransport {
+        fn new(mut responses: Vec<Result<HttpResponse>>) -> Arc<StubTransport> {
+            responses.reverse();
+            Arc::new(StubTransport {
+                responses: Mutex::new(responses),
+                requests: Mutex::new(Vec::new()),
+                sleeps: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl HttpTransport for StubTransport {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+            self.requests.lock().unwrap().push(request);
+            self.responses
+                .lock()
+                .unwrap()
+                .pop()
+                .expect("no response left")
+        }
+
+        // Records the wait instead of sleeping on a runtime timer
+        async fn sleep(&self, duration: Duration) {
+            self.sleeps.lock().unwrap().push(duration);
+        }
+    }
+
+    fn json(status: u16, body: &str) -> Result<HttpResponse> {
+        Ok(HttpResponse::new(
+            StatusCode::from_u16(status).unwrap(),
+            HeaderMap::new(),
+            body.as_bytes().to_vec(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport() {
+        let transport = StubTransport::new(vec![json(200, r#"{"accounts":[]}"#)]);
+        let options = Options::builder()
+            .api_key("test-key")
+            .base_url("http://stub/")
+            .timeout(Duration::from_secs(5))
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let accounts = Accounts::new(options).accounts().await.unwrap();
+        assert!(accounts.accounts.is_empty());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!("GET", requests[0].method.as_str());
+        assert_eq!("http://stub/accounts/v3/accounts", requests[0].url.as_str());
+        assert_eq!("test-key", requests[0].headers["x-api-key"]);
+        assert_eq!(Some(Duration::from_secs(5)), requests[0].timeout);
+        assert_eq!(None, requests[0].body);
+    }
+
+    #[tokio::test]
+    async fn test_transport_errors() {
+        let transport = StubTransport::new(vec![
+            Err(Error::Transport(TransportError::connect(
+                "connection refused",
+            ))),
+            json(200, r#"{"accounts":[]}"#),
+            Err(Error::Transport(TransportError::new("connection reset"))),
+        ]);
+        let mut policy = RetryPolicy::new();
+        policy.set_initial_backoff(Duration::from_millis(1));
+        let options = Options::builder()
+            .api_key("test-key")
+            .transport(transport.clone())
+            .retry_policy(policy)
+            .build()
+            .unwrap();
+        let client = Accounts::new(options);
+
+        // Connection errors are retried, other transport errors are not
+        assert!(client.accounts().await.is_ok());
+        match client.accounts().await {
+            Err(Error::Transport(e)) => {
+                assert!(!e.is_connect());
+                assert_eq!("connection reset", e.to_string());
+            }
+            other => panic!("Expected transport error, got {:?}", other),
+        }
+        assert_eq!(3, transport.requests.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn test_transport_sleep() {
+        let transport = StubTransport::new(vec![
+            json(503, r#"{"errors":[]}"#),
+            json(200, r#"{"accounts":[]}"#),
+        ]);
+        let mut policy = RetryPolicy::new();
+        policy.set_initial_backoff(Duration::from_secs(60));
+        policy.set_max_backoff(Duration::from_secs(60));
+        policy.set_jitter(false);
+        let options = Options::builder()
+            .api_key("test-key")
+            .transport(transport.clone())
+            .retry_policy(policy)
+            .rate_limiter(RateLimiter::new(1, Duration::from_secs(3600)))
+            .build()
+            .unwrap();
+
+        // Backoff and rate limit waits go through the transport
+        assert!(Accounts::new(options).accounts().await.is_ok());
+        assert_eq!(2, transport.requests.lock().unwrap().len());
+        let sleeps = transport.sleeps.lock().unwrap();
+        assert_eq!(2, sleeps.len());
+        assert_eq!(Duration::from_secs(60), sleeps[0]);
+        assert!(sleeps[1] > Duration::from_secs(3000), "{:?}", sleeps[1]);
+    }
+
+    #[tokio::test]
+    async fn test_token_requests() {
+        let transport = StubTransport::new(vec![
+            json(200, r#"{"access_token":"token-1","expires_in":3600}"#),
+            json(200, r#"{"accounts":[]}"#),
+        ]);
+        let mut config = OAuthConfig::new("http://stub/oauth/token", "client");
+        config.set_client_secret("secret");
+        let options = Options::builder()
+            .api_key("test-key")
+            .transport(transport.clone())
+            .authenticator(Authenticator::client_credentials(config))
+            .build()
+            .unwrap();
+        assert!(Accounts::new(options).accounts().await.is_ok());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!("http://stub/oauth/token", requests[0].url.as_str());
+        assert_eq!(
+            "application/x-www-form-urlencoded",
+            requests[0].headers["content-type"]
+        );
+        assert_eq!(
+            b"client_id=client&client_secret=secret&grant_type=client_credentials".to_vec(),
+            requests[0].body.clone().unwrap()
+        );
+        assert_eq!("Bearer token-1", requests[1].headers["authorization"]);
+    }
+
+    #[test]
+    fn test_transport_conflicts() {
+        let transport = StubTransport::new(Vec::new());
+        let result = Options::builder()
+            .api_key("test-key")
+            .transport(transport.clone())
+            .client(Client::new())
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+        let result = Options::builder()
+            .api_key("test-key")
+            .transport(transport)
+            .user_agent("agent")
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let mut options = Options::new_dev(String::from("test-key"));
+        options.set_transport(StubTransport::new(Vec::new()));
+        let result = options.set_connect_timeout(Duration::from_secs(1));
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+}